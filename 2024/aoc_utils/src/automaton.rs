@@ -0,0 +1,415 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::Hash;
+
+use rayon::prelude::*;
+
+use crate::cycle::{fast_forward, Cycle};
+use crate::grid::Grid2D;
+use crate::parsing::Pos;
+use crate::regions::Connectivity;
+
+/// What a dense automaton sees past the border of its grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edges {
+    /// Outside cells always hold the dead value.
+    Dead,
+    /// The grid is a torus, leaving on one side comes back on the other.
+    Wrap,
+    /// The border cells are reflected, the cell past index 0 is index 0.
+    Mirror,
+}
+
+impl Edges {
+    fn resolve(self, i: isize, len: usize) -> Option<usize> {
+        let len = len as isize;
+        match self {
+            Edges::Dead => (0..len).contains(&i).then_some(i as usize),
+            Edges::Wrap => Some(i.rem_euclid(len) as usize),
+            Edges::Mirror => {
+                let i = if i < 0 {
+                    -1 - i
+                } else if i >= len {
+                    2 * len - 1 - i
+                } else {
+                    i
+                };
+                Some(i.clamp(0, len - 1) as usize)
+            }
+        }
+    }
+}
+
+/// Cellular automaton over a fixed size [`Grid2D`].
+///
+/// Every generation, `rule(cell, neighbors)` gives the next value of a cell
+/// from its current value and its neighbors, in the order of the
+/// neighborhood offsets. The next grid is written into a second buffer, so
+/// the rule only ever sees the previous generation.
+pub struct Automaton<T, R> {
+    current: Grid2D<T>,
+    next: Grid2D<T>,
+    neighborhood: Connectivity,
+    edges: Edges,
+    dead: T,
+    rule: R,
+    generation: usize,
+}
+
+fn next_cell<T, R>(
+    grid: &Grid2D<T>,
+    offsets: &[(isize, isize)],
+    edges: Edges,
+    dead: &T,
+    rule: &R,
+    pos: Pos<usize>,
+) -> T
+where
+    R: Fn(&T, &[&T]) -> T,
+{
+    let mut around = [dead; 8];
+    for (slot, &(dl, dc)) in around.iter_mut().zip(offsets) {
+        let line = edges.resolve(pos.line as isize + dl, grid.height());
+        let column = edges.resolve(pos.column as isize + dc, grid.width());
+        if let (Some(line), Some(column)) = (line, column) {
+            *slot = &grid[Pos { line, column }];
+        }
+    }
+    rule(&grid[pos], &around[..offsets.len()])
+}
+
+impl<T, R> Automaton<T, R>
+where
+    T: Clone,
+    R: Fn(&T, &[&T]) -> T,
+{
+    /// Moore neighborhood and dead edges, change them with
+    /// [`Self::with_neighborhood`] and [`Self::with_edges`].
+    pub fn new(grid: Grid2D<T>, dead: T, rule: R) -> Self {
+        Self {
+            next: grid.clone(),
+            current: grid,
+            neighborhood: Connectivity::Eight,
+            edges: Edges::Dead,
+            dead,
+            rule,
+            generation: 0,
+        }
+    }
+
+    pub fn with_neighborhood(mut self, neighborhood: Connectivity) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    pub fn with_edges(mut self, edges: Edges) -> Self {
+        self.edges = edges;
+        self
+    }
+
+    pub fn grid(&self) -> &Grid2D<T> {
+        &self.current
+    }
+
+    pub fn into_grid(self) -> Grid2D<T> {
+        self.current
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn next_grid(&self, grid: &Grid2D<T>) -> Grid2D<T> {
+        let offsets = self.neighborhood.offsets();
+        let cells = grid
+            .positions()
+            .map(|pos| next_cell(grid, offsets, self.edges, &self.dead, &self.rule, pos))
+            .collect();
+        Grid2D::from_vec(grid.width(), grid.height(), cells)
+    }
+
+    pub fn step(&mut self) {
+        let Self {
+            current,
+            next,
+            neighborhood,
+            edges,
+            dead,
+            rule,
+            ..
+        } = self;
+        let offsets = neighborhood.offsets();
+        for (i, cell) in next.cells_mut().iter_mut().enumerate() {
+            *cell = next_cell(current, offsets, *edges, dead, rule, current.pos_of(i));
+        }
+        std::mem::swap(current, next);
+        self.generation += 1;
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+}
+
+impl<T, R> Automaton<T, R>
+where
+    T: Clone + Send + Sync,
+    R: Fn(&T, &[&T]) -> T + Sync,
+{
+    /// Same as [`Self::step`], the cells being shared among rayon's threads.
+    pub fn par_step(&mut self) {
+        let Self {
+            current,
+            next,
+            neighborhood,
+            edges,
+            dead,
+            rule,
+            ..
+        } = self;
+        let offsets = neighborhood.offsets();
+        next.cells_mut()
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(i, cell)| {
+                *cell = next_cell(current, offsets, *edges, dead, rule, current.pos_of(i));
+            });
+        std::mem::swap(current, next);
+        self.generation += 1;
+    }
+}
+
+impl<T, R> Automaton<T, R>
+where
+    T: Clone + Eq + Hash,
+    R: Fn(&T, &[&T]) -> T,
+{
+    /// Runs `generations` more steps, skipping whole laps once a grid repeats.
+    ///
+    /// Returns the cycle when one was found on the way.
+    pub fn advance(&mut self, generations: usize) -> Option<Cycle> {
+        let (grid, cycle) = fast_forward(self.current.clone(), |g| self.next_grid(g), generations);
+        self.current = grid;
+        self.generation += generations;
+        cycle
+    }
+}
+
+/// Cellular automaton over an unbounded plane where almost every cell is dead.
+///
+/// Only the live cells are stored; a dead cell with live neighbors is still
+/// given to the rule, so births happen as on a dense grid.
+pub struct SparseAutomaton<T, R> {
+    cells: BTreeMap<Pos<i64>, T>,
+    neighborhood: Connectivity,
+    dead: T,
+    rule: R,
+    generation: usize,
+}
+
+impl<T, R> SparseAutomaton<T, R>
+where
+    T: Clone + PartialEq,
+    R: Fn(&T, &[&T]) -> T,
+{
+    /// Moore neighborhood, change it with [`Self::with_neighborhood`].
+    pub fn new(cells: impl IntoIterator<Item = (Pos<i64>, T)>, dead: T, rule: R) -> Self {
+        let cells = cells.into_iter().filter(|(_, v)| *v != dead).collect();
+        Self {
+            cells,
+            neighborhood: Connectivity::Eight,
+            dead,
+            rule,
+            generation: 0,
+        }
+    }
+
+    pub fn with_neighborhood(mut self, neighborhood: Connectivity) -> Self {
+        self.neighborhood = neighborhood;
+        self
+    }
+
+    pub fn cells(&self) -> &BTreeMap<Pos<i64>, T> {
+        &self.cells
+    }
+
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn around(&self, pos: Pos<i64>) -> impl Iterator<Item = Pos<i64>> + '_ {
+        self.neighborhood
+            .offsets()
+            .iter()
+            .map(move |&(dl, dc)| Pos {
+                line: pos.line + dl as i64,
+                column: pos.column + dc as i64,
+            })
+    }
+
+    /// Live cells and every cell next to one, the only ones that may be
+    /// alive next generation.
+    fn candidates(&self, cells: &BTreeMap<Pos<i64>, T>) -> BTreeSet<Pos<i64>> {
+        cells
+            .keys()
+            .flat_map(|&pos| std::iter::once(pos).chain(self.around(pos)))
+            .collect()
+    }
+
+    fn next_cell(&self, cells: &BTreeMap<Pos<i64>, T>, pos: Pos<i64>) -> Option<(Pos<i64>, T)> {
+        let mut around = [&self.dead; 8];
+        for (slot, p) in around.iter_mut().zip(self.around(pos)) {
+            *slot = cells.get(&p).unwrap_or(&self.dead);
+        }
+        let len = self.neighborhood.offsets().len();
+        let value = (self.rule)(cells.get(&pos).unwrap_or(&self.dead), &around[..len]);
+        (value != self.dead).then_some((pos, value))
+    }
+
+    fn next_cells(&self, cells: &BTreeMap<Pos<i64>, T>) -> BTreeMap<Pos<i64>, T> {
+        self.candidates(cells)
+            .into_iter()
+            .filter_map(|pos| self.next_cell(cells, pos))
+            .collect()
+    }
+
+    pub fn step(&mut self) {
+        self.cells = self.next_cells(&self.cells);
+        self.generation += 1;
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+}
+
+impl<T, R> SparseAutomaton<T, R>
+where
+    T: Clone + PartialEq + Send + Sync,
+    R: Fn(&T, &[&T]) -> T + Sync,
+{
+    /// Same as [`Self::step`], the candidate cells being shared among rayon's
+    /// threads.
+    pub fn par_step(&mut self) {
+        let candidates: Vec<Pos<i64>> = self.candidates(&self.cells).into_iter().collect();
+        let cells = candidates
+            .into_par_iter()
+            .filter_map(|pos| self.next_cell(&self.cells, pos))
+            .collect();
+        self.cells = cells;
+        self.generation += 1;
+    }
+}
+
+impl<T, R> SparseAutomaton<T, R>
+where
+    T: Clone + Eq + Hash,
+    R: Fn(&T, &[&T]) -> T,
+{
+    /// Runs `generations` more steps, skipping whole laps once the live cells
+    /// repeat exactly (moving patterns never do).
+    pub fn advance(&mut self, generations: usize) -> Option<Cycle> {
+        let (cells, cycle) = fast_forward(self.cells.clone(), |c| self.next_cells(c), generations);
+        self.cells = cells;
+        self.generation += generations;
+        cycle
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(cell: &bool, around: &[&bool]) -> bool {
+        let alive = around.iter().filter(|&&&c| c).count();
+        alive == 3 || (*cell && alive == 2)
+    }
+
+    fn parse(input: &str) -> Grid2D<bool> {
+        Grid2D::parse_with(input, |ch| ch == '#')
+    }
+
+    fn render(grid: &Grid2D<bool>) -> String {
+        grid.render(|_, &alive| if alive { '#' } else { '.' })
+    }
+
+    #[test]
+    fn test_blinker_and_parallel() {
+        let mut blinker = Automaton::new(parse(".....\n..#..\n..#..\n..#..\n....."), false, life);
+        blinker.step();
+        assert_eq!(render(blinker.grid()), ".....\n.....\n.###.\n.....\n.....");
+        blinker.par_step();
+        assert_eq!(render(blinker.grid()), ".....\n..#..\n..#..\n..#..\n.....");
+        assert_eq!(blinker.generation(), 2);
+
+        let cycle = blinker.advance(1_000_001).unwrap();
+        assert_eq!((cycle.mu, cycle.lambda), (0, 2));
+        assert_eq!(render(blinker.grid()), ".....\n.....\n.###.\n.....\n.....");
+    }
+
+    #[test]
+    fn test_edges() {
+        // a blinker against the left border
+        let grid = parse(".....\n#....\n#....\n#....\n.....");
+        let mut dead = Automaton::new(grid.clone(), false, life);
+        dead.step();
+        assert_eq!(render(dead.grid()), ".....\n.....\n##...\n.....\n.....");
+
+        let mut wrap = Automaton::new(grid.clone(), false, life).with_edges(Edges::Wrap);
+        wrap.step();
+        assert_eq!(render(wrap.grid()), ".....\n.....\n##..#\n.....\n.....");
+
+        let mut mirror = Automaton::new(grid, false, life).with_edges(Edges::Mirror);
+        mirror.step();
+        // it sees its reflection, the pair behaves like a 2x3 block
+        assert_eq!(render(mirror.grid()), ".....\n#....\n.#...\n#....\n.....");
+
+        // von Neumann: a cell is born between two live orthogonal cells
+        let mut cross = Automaton::new(parse("#.#"), false, |c: &bool, around: &[&bool]| {
+            *c || around.iter().filter(|&&&a| a).count() == 2
+        })
+        .with_neighborhood(Connectivity::Four);
+        cross.step();
+        assert_eq!(render(cross.grid()), "###");
+    }
+
+    #[test]
+    fn test_glider() {
+        let glider = ".#...\n..#..\n###..\n.....\n.....";
+        // on a 5x5 torus the glider is back after 4 generations per cell
+        let mut torus = Automaton::new(parse(glider), false, life).with_edges(Edges::Wrap);
+        let cycle = torus.advance(1000).unwrap();
+        assert_eq!((cycle.mu, cycle.lambda), (0, 20));
+        assert_eq!(render(torus.grid()), glider);
+
+        // on the plane it travels one cell down and right every 4 generations
+        let grid = parse(glider);
+        let cells = grid.iter().filter(|(_, &alive)| alive).map(|(p, _)| {
+            let p = Pos {
+                line: p.line as i64,
+                column: p.column as i64,
+            };
+            (p, true)
+        });
+        let mut plane = SparseAutomaton::new(cells, false, life);
+        let start: Vec<Pos<i64>> = plane.cells().keys().copied().collect();
+        plane.run(4);
+        plane.par_step();
+        plane.run(3);
+        let moved: Vec<Pos<i64>> = plane
+            .cells()
+            .keys()
+            .map(|p| Pos {
+                line: p.line - 2,
+                column: p.column - 2,
+            })
+            .collect();
+        assert_eq!(moved, start);
+        assert_eq!(plane.advance(40), None);
+        assert_eq!(plane.generation(), 48);
+        assert_eq!(plane.cells().len(), 5);
+    }
+}
@@ -0,0 +1,165 @@
+use std::fmt::{self, Debug, Formatter};
+
+/// Fixed capacity set of small integers, one bit each.
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct BitSet {
+    words: Vec<u64>,
+}
+
+impl BitSet {
+    /// Empty set able to hold `0..capacity`.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            words: vec![0; capacity.div_ceil(64)],
+        }
+    }
+
+    /// Set holding all of `0..capacity`.
+    pub fn full(capacity: usize) -> Self {
+        let mut set = Self::new(capacity);
+        for i in 0..capacity {
+            set.insert(i);
+        }
+        set
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.words.len() * 64
+    }
+
+    /// Adds `i`, returns false if it was already there.
+    pub fn insert(&mut self, i: usize) -> bool {
+        let (word, bit) = (i / 64, 1 << (i % 64));
+        let fresh = self.words[word] & bit == 0;
+        self.words[word] |= bit;
+        fresh
+    }
+
+    /// Removes `i`, returns false if it wasn't there.
+    pub fn remove(&mut self, i: usize) -> bool {
+        let (word, bit) = (i / 64, 1 << (i % 64));
+        let present = self.words[word] & bit != 0;
+        self.words[word] &= !bit;
+        present
+    }
+
+    pub fn contains(&self, i: usize) -> bool {
+        self.words
+            .get(i / 64)
+            .is_some_and(|w| w & (1 << (i % 64)) != 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|&w| w == 0)
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// Members in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        self.words.iter().enumerate().flat_map(|(k, &word)| {
+            let mut rest = word;
+            std::iter::from_fn(move || {
+                (rest != 0).then(|| {
+                    let bit = rest.trailing_zeros() as usize;
+                    rest &= rest - 1;
+                    k * 64 + bit
+                })
+            })
+        })
+    }
+
+    /// Members of both sets.
+    pub fn intersection(&self, other: &Self) -> Self {
+        Self {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(a, b)| a & b)
+                .collect(),
+        }
+    }
+
+    /// Members of `self` missing from `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        Self {
+            words: self
+                .words
+                .iter()
+                .enumerate()
+                .map(|(k, a)| a & !other.words.get(k).copied().unwrap_or(0))
+                .collect(),
+        }
+    }
+
+    /// Size of the intersection, without building it.
+    pub fn intersection_len(&self, other: &Self) -> usize {
+        self.words
+            .iter()
+            .zip(&other.words)
+            .map(|(a, b)| (a & b).count_ones() as usize)
+            .sum()
+    }
+
+    pub fn union_with(&mut self, other: &Self) {
+        if self.words.len() < other.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        for (a, b) in self.words.iter_mut().zip(&other.words) {
+            *a |= b;
+        }
+    }
+}
+
+impl Debug for BitSet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+impl FromIterator<usize> for BitSet {
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut set = Self::default();
+        for i in iter {
+            if i >= set.capacity() {
+                set.words.resize(i / 64 + 1, 0);
+            }
+            set.insert(i);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_operations() {
+        let a: BitSet = [1, 5, 64, 130].into_iter().collect();
+        let mut b = BitSet::new(200);
+        assert!(b.insert(5));
+        assert!(!b.insert(5));
+        b.insert(130);
+        b.insert(199);
+
+        assert_eq!(a.len(), 4);
+        assert!(a.contains(64) && !a.contains(63) && !a.contains(1000));
+        assert_eq!(a.intersection(&b).iter().collect::<Vec<_>>(), vec![5, 130]);
+        assert_eq!(a.intersection_len(&b), 2);
+        assert_eq!(a.difference(&b).iter().collect::<Vec<_>>(), vec![1, 64]);
+
+        b.union_with(&a);
+        assert_eq!(b.len(), 5);
+        assert!(b.remove(199) && !b.remove(199));
+        assert_eq!(format!("{b:?}"), "{1, 5, 64, 130}");
+        assert_eq!(BitSet::full(70).len(), 70);
+    }
+}
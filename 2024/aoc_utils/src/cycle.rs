@@ -0,0 +1,242 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Shape of an eventually periodic sequence `x0, f(x0), f(f(x0)), ...`.
+///
+/// The first `mu` states are the tail, after which the sequence repeats every
+/// `lambda` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub mu: usize,
+    pub lambda: usize,
+}
+
+impl Cycle {
+    /// Smallest step index reaching the same state as step `n`.
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.mu {
+            n
+        } else {
+            self.mu + (n - self.mu) % self.lambda
+        }
+    }
+}
+
+/// Brent's cycle detection, using O(1) memory and fewer `step` calls than Floyd.
+pub fn brent<S, F>(start: S, step: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: Fn(&S) -> S,
+{
+    // 1) find lambda by racing a hare against a tortoise teleported at powers of two
+    let mut power = 1;
+    let mut lambda = 1;
+    let mut tortoise = start.clone();
+    let mut hare = step(&start);
+    while tortoise != hare {
+        if power == lambda {
+            tortoise = hare.clone();
+            power *= 2;
+            lambda = 0;
+        }
+        hare = step(&hare);
+        lambda += 1;
+    }
+
+    // 2) put the hare lambda steps ahead, then walk both until they meet at mu
+    let mut tortoise = start.clone();
+    let mut hare = start;
+    for _ in 0..lambda {
+        hare = step(&hare);
+    }
+    let mut mu = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+
+    Cycle { mu, lambda }
+}
+
+/// Floyd's tortoise and hare cycle detection.
+pub fn floyd<S, F>(start: S, step: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: Fn(&S) -> S,
+{
+    // 1) hare runs twice as fast until both meet inside the cycle
+    let mut tortoise = step(&start);
+    let mut hare = step(&step(&start));
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+
+    // 2) restart the tortoise, both at the same pace meet at the cycle entry
+    let mut mu = 0;
+    let mut tortoise = start;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        mu += 1;
+    }
+
+    // 3) walk once around the cycle to measure it
+    let mut lambda = 1;
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        lambda += 1;
+    }
+
+    Cycle { mu, lambda }
+}
+
+/// Records every visited state until one repeats.
+///
+/// `step` returning `None` means the simulation ended (e.g. the guard left the
+/// map), in which case there is no cycle.
+pub fn detect<S, F>(start: S, step: F) -> Option<Cycle>
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> Option<S>,
+{
+    History::record(start, step).map(|h| h.cycle)
+}
+
+/// State reached after `n` steps, fast-forwarding through the period once a
+/// state repeats.
+pub fn state_after<S, F>(start: S, step: F, n: usize) -> S
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    fast_forward(start, step, n).0
+}
+
+/// [`state_after`], along with the cycle when one shows up within `n` steps.
+///
+/// Never steps more than `n` times, so a state space that doesn't repeat
+/// (or not soon enough) is simply simulated.
+pub fn fast_forward<S, F>(start: S, mut step: F, n: usize) -> (S, Option<Cycle>)
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    let mut budget = n;
+    let mut last = None;
+    let history = History::record(start.clone(), |state| {
+        if budget == 0 {
+            return None;
+        }
+        budget -= 1;
+        let next = step(state);
+        last = Some(next.clone());
+        Some(next)
+    });
+    match history {
+        Some(history) => (history.state_at(n).clone(), Some(history.cycle)),
+        None => (last.unwrap_or(start), None),
+    }
+}
+
+/// Every state of the tail and first lap of the cycle, in visiting order.
+#[derive(Debug, Clone)]
+pub struct History<S> {
+    pub states: Vec<S>,
+    pub cycle: Cycle,
+}
+
+impl<S> History<S>
+where
+    S: Clone + Eq + Hash,
+{
+    pub fn record<F>(start: S, mut step: F) -> Option<Self>
+    where
+        F: FnMut(&S) -> Option<S>,
+    {
+        let mut seen: HashMap<S, usize> = HashMap::new();
+        let mut states = Vec::new();
+        let mut current = start;
+        loop {
+            if let Some(&first) = seen.get(&current) {
+                let cycle = Cycle {
+                    mu: first,
+                    lambda: states.len() - first,
+                };
+                tracing::debug!(?cycle, "state revisited");
+                return Some(Self { states, cycle });
+            }
+            seen.insert(current.clone(), states.len());
+            let next = step(&current)?;
+            states.push(current);
+            current = next;
+        }
+    }
+
+    /// State after `n` steps.
+    pub fn state_at(&self, n: usize) -> &S {
+        &self.states[self.cycle.reduce(n)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn f(x: &u32) -> u32 {
+        (x * x + 1) % 255
+    }
+
+    #[test]
+    fn test_detectors_agree() {
+        for start in 0..255 {
+            let expected = detect(start, |x| Some(f(x))).unwrap();
+            assert_eq!(brent(start, f), expected, "brent from {start}");
+            assert_eq!(floyd(start, f), expected, "floyd from {start}");
+        }
+    }
+
+    #[test]
+    fn test_known_cycle() {
+        // 0 -> 1 -> 2 -> 3 -> 4 -> 2
+        let step = |x: &u8| if *x == 4 { 2 } else { x + 1 };
+        let cycle = Cycle { mu: 2, lambda: 3 };
+        assert_eq!(brent(0, step), cycle);
+        assert_eq!(floyd(0, step), cycle);
+        assert_eq!(detect(0, |x| Some(step(x))), Some(cycle));
+    }
+
+    #[test]
+    fn test_detect_terminates() {
+        assert_eq!(detect(0, |x: &u8| (*x < 10).then_some(x + 1)), None);
+    }
+
+    #[test]
+    fn test_state_after() {
+        let mut naive = 3;
+        for n in 0..1000 {
+            assert_eq!(state_after(3, f, n), naive);
+            naive = f(&naive);
+        }
+        let history = History::record(3, |x| Some(f(x))).unwrap();
+        assert_eq!(
+            *history.state_at(1_000_000_000),
+            state_after(3, f, 1_000_000_000)
+        );
+    }
+
+    #[test]
+    fn test_state_after_no_cycle() {
+        // never repeats, only the n steps asked for are taken
+        assert_eq!(state_after(0u64, |x| x + 1, 5), 5);
+        assert_eq!(state_after(0u64, |x| x + 1, 0), 0);
+        assert_eq!(fast_forward(0u64, |x| x + 1, 5), (5, None));
+        let step = |x: &u8| if *x == 4 { 2 } else { x + 1 };
+        assert_eq!(
+            fast_forward(0, step, 100),
+            (4, Some(Cycle { mu: 2, lambda: 3 }))
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use std::ops::{Add, Range, Sub};
+
+/// Prefix sums with point updates in `O(log n)`, a binary indexed tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fenwick<T> {
+    /// 1-based: `tree[i]` sums the `i & -i` cells ending at cell `i - 1`.
+    tree: Vec<T>,
+}
+
+impl<T> Fenwick<T>
+where
+    T: Copy + Default + Add<Output = T> + Sub<Output = T>,
+{
+    /// `len` cells, all zero.
+    pub fn new(len: usize) -> Self {
+        Self {
+            tree: vec![T::default(); len + 1],
+        }
+    }
+
+    pub fn from_slice(cells: &[T]) -> Self {
+        let mut tree = vec![T::default(); cells.len() + 1];
+        tree[1..].copy_from_slice(cells);
+        // push every partial sum to its parent once, in O(n)
+        for i in 1..tree.len() {
+            let parent = i + (i & i.wrapping_neg());
+            if parent < tree.len() {
+                tree[parent] = tree[parent] + tree[i];
+            }
+        }
+        Self { tree }
+    }
+
+    pub fn len(&self) -> usize {
+        self.tree.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds `delta` to cell `i`.
+    pub fn add(&mut self, i: usize, delta: T) {
+        let mut i = i + 1;
+        while i < self.tree.len() {
+            self.tree[i] = self.tree[i] + delta;
+            i += i & i.wrapping_neg();
+        }
+    }
+
+    /// Sum of the cells `0..end`.
+    pub fn prefix(&self, end: usize) -> T {
+        let mut sum = T::default();
+        let mut i = end.min(self.len());
+        while i > 0 {
+            sum = sum + self.tree[i];
+            i &= i - 1;
+        }
+        sum
+    }
+
+    pub fn sum(&self, range: Range<usize>) -> T {
+        if range.start >= range.end {
+            return T::default();
+        }
+        self.prefix(range.end) - self.prefix(range.start)
+    }
+
+    pub fn get(&self, i: usize) -> T {
+        self.sum(i..i + 1)
+    }
+
+    pub fn set(&mut self, i: usize, value: T) {
+        let delta = value - self.get(i);
+        self.add(i, delta);
+    }
+}
+
+impl<T> Fenwick<T>
+where
+    T: Copy + Default + Ord + Add<Output = T> + Sub<Output = T>,
+{
+    /// Smallest `i` with `prefix(i + 1) >= target`, or `len()` if the total
+    /// is too small. Cells must not be negative.
+    ///
+    /// With a 1 for every free cell, `lower_bound(k)` is the `k`-th free one.
+    pub fn lower_bound(&self, target: T) -> usize {
+        let mut pos = 0;
+        let mut rest = target;
+        let mut step = (self.tree.len()).next_power_of_two() / 2;
+        while step > 0 {
+            let next = pos + step;
+            if next < self.tree.len() && self.tree[next] < rest {
+                pos = next;
+                rest = rest - self.tree[next];
+            }
+            step /= 2;
+        }
+        pos
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_prefix_sums() {
+        let cells = [3, 1, 4, 1, 5, 9, 2, 6];
+        let mut fenwick = Fenwick::from_slice(&cells);
+        let mut naive = cells.to_vec();
+        assert_eq!(fenwick, {
+            let mut one_by_one = Fenwick::new(cells.len());
+            cells
+                .iter()
+                .enumerate()
+                .for_each(|(i, &v)| one_by_one.add(i, v));
+            one_by_one
+        });
+
+        fenwick.add(2, 10);
+        naive[2] += 10;
+        fenwick.set(7, -6);
+        naive[7] = -6;
+        for start in 0..naive.len() {
+            for end in start..=naive.len() {
+                assert_eq!(fenwick.sum(start..end), naive[start..end].iter().sum());
+            }
+        }
+        assert_eq!(fenwick.get(7), -6);
+        assert_eq!(fenwick.prefix(100), naive.iter().sum());
+    }
+
+    #[test]
+    fn test_lower_bound() {
+        // 1 marks a free cell
+        let free = Fenwick::from_slice(&[0, 1, 1, 0, 0, 1, 0, 1u32]);
+        assert_eq!(free.lower_bound(1), 1);
+        assert_eq!(free.lower_bound(2), 2);
+        assert_eq!(free.lower_bound(3), 5);
+        assert_eq!(free.lower_bound(4), 7);
+        assert_eq!(free.lower_bound(5), 8);
+        assert_eq!(free.lower_bound(0), 0);
+    }
+}
@@ -0,0 +1,547 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+/// Adjacency map `from -> { to }`, the shape of day 5's `topo::Graph<T>`.
+pub type Graph<T> = HashMap<T, HashSet<T>>;
+
+/// Directed capacity graph with a residual edge paired to every edge.
+///
+/// Edge `e` and its residual `e ^ 1` are stored next to each other, so
+/// pushing flow on one frees capacity on the other.
+#[derive(Debug, Clone)]
+pub struct FlowNetwork<T> {
+    ids: HashMap<T, usize>,
+    nodes: Vec<T>,
+    adjacency: Vec<Vec<usize>>,
+    to: Vec<usize>,
+    capacity: Vec<i64>,
+    flow: Vec<i64>,
+}
+
+/// Result of [`FlowNetwork::min_cut`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MinCut<T: Eq + Hash> {
+    pub value: i64,
+    /// Saturated edges going from the source side to the sink side.
+    pub edges: Vec<(T, T)>,
+    pub source_side: HashSet<T>,
+    pub sink_side: HashSet<T>,
+}
+
+impl<T> Default for FlowNetwork<T> {
+    fn default() -> Self {
+        Self {
+            ids: HashMap::new(),
+            nodes: Vec::new(),
+            adjacency: Vec::new(),
+            to: Vec::new(),
+            capacity: Vec::new(),
+            flow: Vec::new(),
+        }
+    }
+}
+
+impl<T> FlowNetwork<T>
+where
+    T: Clone + Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Network with a unit capacity on every edge of `graph`.
+    pub fn from_graph(graph: &Graph<T>) -> Self {
+        let mut network = Self::new();
+        for (from, targets) in graph {
+            for to in targets {
+                network.add_edge(from.clone(), to.clone(), 1);
+            }
+        }
+        network
+    }
+
+    fn id(&mut self, node: T) -> usize {
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.adjacency.push(Vec::new());
+        id
+    }
+
+    fn push_pair(&mut self, a: usize, b: usize, forward: i64, backward: i64) {
+        for (from, to, cap) in [(a, b, forward), (b, a, backward)] {
+            self.adjacency[from].push(self.to.len());
+            self.to.push(to);
+            self.capacity.push(cap);
+            self.flow.push(0);
+        }
+    }
+
+    pub fn add_edge(&mut self, from: T, to: T, capacity: i64) {
+        let (a, b) = (self.id(from), self.id(to));
+        self.push_pair(a, b, capacity, 0);
+    }
+
+    /// Edge usable in both directions, sharing the same capacity.
+    pub fn add_undirected(&mut self, a: T, b: T, capacity: i64) {
+        let (a, b) = (self.id(a), self.id(b));
+        self.push_pair(a, b, capacity, capacity);
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    fn residual(&self, e: usize) -> i64 {
+        self.capacity[e] - self.flow[e]
+    }
+
+    fn push(&mut self, e: usize, amount: i64) {
+        self.flow[e] += amount;
+        self.flow[e ^ 1] -= amount;
+    }
+
+    /// Drops the flow computed so far.
+    pub fn reset(&mut self) {
+        self.flow.iter_mut().for_each(|f| *f = 0);
+    }
+
+    fn endpoints(&self, source: &T, sink: &T) -> Option<(usize, usize)> {
+        Some((*self.ids.get(source)?, *self.ids.get(sink)?))
+    }
+
+    /// Maximum flow by Edmonds–Karp (shortest augmenting paths).
+    pub fn edmonds_karp(&mut self, source: &T, sink: &T) -> i64 {
+        let Some((s, t)) = self.endpoints(source, sink) else {
+            return 0;
+        };
+        let mut total = 0;
+        loop {
+            // 1) BFS for the shortest path in the residual graph
+            let mut parent_edge = vec![usize::MAX; self.nodes.len()];
+            let mut queue = VecDeque::from([s]);
+            while let Some(u) = queue.pop_front() {
+                for &e in &self.adjacency[u] {
+                    let v = self.to[e];
+                    if v != s && parent_edge[v] == usize::MAX && self.residual(e) > 0 {
+                        parent_edge[v] = e;
+                        queue.push_back(v);
+                    }
+                }
+            }
+            if parent_edge[t] == usize::MAX {
+                return total;
+            }
+
+            // 2) bottleneck along the path, then push it
+            let mut path = Vec::new();
+            let mut v = t;
+            while v != s {
+                let e = parent_edge[v];
+                path.push(e);
+                v = self.to[e ^ 1];
+            }
+            let bottleneck = path.iter().map(|&e| self.residual(e)).min().unwrap_or(0);
+            for e in path {
+                self.push(e, bottleneck);
+            }
+            total += bottleneck;
+        }
+    }
+
+    /// Maximum flow by Dinic (level graph and blocking flows).
+    pub fn dinic(&mut self, source: &T, sink: &T) -> i64 {
+        let Some((s, t)) = self.endpoints(source, sink) else {
+            return 0;
+        };
+        if s == t {
+            // the sink is reached with the unbounded limit, nothing flows
+            return 0;
+        }
+        let mut total = 0;
+        while let Some(level) = self.levels(s, t) {
+            let mut next = vec![0; self.nodes.len()];
+            loop {
+                let pushed = self.blocking(s, t, i64::MAX, &level, &mut next);
+                if pushed == 0 {
+                    break;
+                }
+                total += pushed;
+            }
+        }
+        total
+    }
+
+    fn levels(&self, s: usize, t: usize) -> Option<Vec<usize>> {
+        let mut level = vec![usize::MAX; self.nodes.len()];
+        level[s] = 0;
+        let mut queue = VecDeque::from([s]);
+        while let Some(u) = queue.pop_front() {
+            for &e in &self.adjacency[u] {
+                let v = self.to[e];
+                if level[v] == usize::MAX && self.residual(e) > 0 {
+                    level[v] = level[u] + 1;
+                    queue.push_back(v);
+                }
+            }
+        }
+        (level[t] != usize::MAX).then_some(level)
+    }
+
+    fn blocking(
+        &mut self,
+        u: usize,
+        t: usize,
+        limit: i64,
+        level: &[usize],
+        next: &mut [usize],
+    ) -> i64 {
+        if u == t {
+            return limit;
+        }
+        while next[u] < self.adjacency[u].len() {
+            let e = self.adjacency[u][next[u]];
+            let v = self.to[e];
+            if level[v] == level[u] + 1 && self.residual(e) > 0 {
+                let pushed = self.blocking(v, t, limit.min(self.residual(e)), level, next);
+                if pushed > 0 {
+                    self.push(e, pushed);
+                    return pushed;
+                }
+            }
+            next[u] += 1;
+        }
+        0
+    }
+
+    /// Minimum `source`/`sink` cut, from a fresh maximum flow.
+    pub fn min_cut(&mut self, source: &T, sink: &T) -> MinCut<T> {
+        self.reset();
+        let value = self.dinic(source, sink);
+
+        // nodes still reachable from the source in the residual graph
+        let mut reached = vec![false; self.nodes.len()];
+        if let Some(&s) = self.ids.get(source) {
+            reached[s] = true;
+            let mut queue = VecDeque::from([s]);
+            while let Some(u) = queue.pop_front() {
+                for &e in &self.adjacency[u] {
+                    let v = self.to[e];
+                    if !reached[v] && self.residual(e) > 0 {
+                        reached[v] = true;
+                        queue.push_back(v);
+                    }
+                }
+            }
+        }
+
+        let mut edges = Vec::new();
+        for (u, out) in self.adjacency.iter().enumerate() {
+            for &e in out {
+                let v = self.to[e];
+                if reached[u] && !reached[v] && self.capacity[e] > 0 {
+                    edges.push((self.nodes[u].clone(), self.nodes[v].clone()));
+                }
+            }
+        }
+        let (source_side, sink_side) = self
+            .nodes
+            .iter()
+            .cloned()
+            .partition(|n| reached[self.ids[n]]);
+
+        MinCut {
+            value,
+            edges,
+            source_side,
+            sink_side,
+        }
+    }
+}
+
+/// Maximum bipartite matching by Hopcroft–Karp.
+///
+/// `edges` maps every left node to the right nodes it may be paired with,
+/// e.g. allergens to the ingredients that could contain them.
+pub fn hopcroft_karp<L, R>(edges: &HashMap<L, HashSet<R>>) -> HashMap<L, R>
+where
+    L: Clone + Eq + Hash,
+    R: Clone + Eq + Hash,
+{
+    // index both sides
+    let left: Vec<&L> = edges.keys().collect();
+    let mut right_ids: HashMap<&R, usize> = HashMap::new();
+    let mut right: Vec<&R> = Vec::new();
+    let adjacency: Vec<Vec<usize>> = left
+        .iter()
+        .map(|l| {
+            edges[*l]
+                .iter()
+                .map(|r| {
+                    *right_ids.entry(r).or_insert_with(|| {
+                        right.push(r);
+                        right.len() - 1
+                    })
+                })
+                .collect()
+        })
+        .collect();
+
+    const FREE: usize = usize::MAX;
+    let mut pair_left = vec![FREE; left.len()];
+    let mut pair_right = vec![FREE; right.len()];
+    let mut dist = vec![0; left.len()];
+
+    // BFS layering from the free left nodes, true if an augmenting path exists
+    let bfs = |pair_left: &[usize], pair_right: &[usize], dist: &mut [usize]| {
+        let mut queue = VecDeque::new();
+        for (l, d) in dist.iter_mut().enumerate() {
+            *d = if pair_left[l] == FREE {
+                queue.push_back(l);
+                0
+            } else {
+                usize::MAX
+            };
+        }
+        let mut found = false;
+        while let Some(l) = queue.pop_front() {
+            for &r in &adjacency[l] {
+                match pair_right[r] {
+                    FREE => found = true,
+                    next if dist[next] == usize::MAX => {
+                        dist[next] = dist[l] + 1;
+                        queue.push_back(next);
+                    }
+                    _ => {}
+                }
+            }
+        }
+        found
+    };
+
+    fn dfs(
+        l: usize,
+        adjacency: &[Vec<usize>],
+        pair_left: &mut [usize],
+        pair_right: &mut [usize],
+        dist: &mut [usize],
+    ) -> bool {
+        for &r in &adjacency[l] {
+            let next = pair_right[r];
+            if next == FREE
+                || (dist[next] == dist[l] + 1 && dfs(next, adjacency, pair_left, pair_right, dist))
+            {
+                pair_left[l] = r;
+                pair_right[r] = l;
+                return true;
+            }
+        }
+        // dead end, drop it from this phase
+        dist[l] = usize::MAX;
+        false
+    }
+
+    while bfs(&pair_left, &pair_right, &mut dist) {
+        for l in 0..left.len() {
+            if pair_left[l] == FREE {
+                dfs(l, &adjacency, &mut pair_left, &mut pair_right, &mut dist);
+            }
+        }
+    }
+
+    pair_left
+        .iter()
+        .enumerate()
+        .filter(|(_, &r)| r != FREE)
+        .map(|(l, &r)| (left[l].clone(), right[r].clone()))
+        .collect()
+}
+
+/// Global minimum cut of an undirected weighted graph by Stoer–Wagner.
+///
+/// Returns the cut weight and the nodes of one side, `None` with fewer than
+/// two nodes.
+pub fn stoer_wagner<T>(edges: &[(T, T, i64)]) -> Option<(i64, HashSet<T>)>
+where
+    T: Clone + Eq + Hash,
+{
+    let mut ids: HashMap<&T, usize> = HashMap::new();
+    let mut nodes: Vec<&T> = Vec::new();
+    for (a, b, _) in edges {
+        for n in [a, b] {
+            ids.entry(n).or_insert_with(|| {
+                nodes.push(n);
+                nodes.len() - 1
+            });
+        }
+    }
+    let n = nodes.len();
+    if n < 2 {
+        return None;
+    }
+    let mut weight = vec![vec![0i64; n]; n];
+    for (a, b, w) in edges {
+        let (a, b) = (ids[a], ids[b]);
+        if a != b {
+            weight[a][b] += w;
+            weight[b][a] += w;
+        }
+    }
+
+    // every super node remembers the original nodes merged into it
+    let mut members: Vec<Vec<usize>> = (0..n).map(|i| vec![i]).collect();
+    let mut alive: Vec<usize> = (0..n).collect();
+    let mut best: Option<(i64, Vec<usize>)> = None;
+
+    while alive.len() > 1 {
+        // 1) maximum adjacency ordering
+        let mut added = vec![false; n];
+        let mut connection = vec![0i64; n];
+        let (mut prev, mut last) = (alive[0], alive[0]);
+        for _ in 0..alive.len() {
+            let next = *alive
+                .iter()
+                .filter(|&&v| !added[v])
+                .max_by_key(|&&v| connection[v])
+                .expect("a node is left to add");
+            added[next] = true;
+            prev = last;
+            last = next;
+            for &v in &alive {
+                connection[v] += weight[next][v];
+            }
+        }
+
+        // 2) cut of the phase separates the last node from the rest
+        let cut = connection[last];
+        if best.as_ref().is_none_or(|(w, _)| cut < *w) {
+            best = Some((cut, members[last].clone()));
+        }
+
+        // 3) merge the last two nodes
+        let merged = std::mem::take(&mut members[last]);
+        members[prev].extend(merged);
+        for &v in &alive {
+            weight[prev][v] += weight[last][v];
+            weight[v][prev] = weight[prev][v];
+        }
+        weight[prev][prev] = 0;
+        alive.retain(|&v| v != last);
+    }
+
+    best.map(|(w, side)| (w, side.into_iter().map(|i| nodes[i].clone()).collect()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn clrs() -> FlowNetwork<&'static str> {
+        let mut network = FlowNetwork::new();
+        for (a, b, c) in [
+            ("s", "v1", 16),
+            ("s", "v2", 13),
+            ("v2", "v1", 4),
+            ("v1", "v3", 12),
+            ("v3", "v2", 9),
+            ("v2", "v4", 14),
+            ("v4", "v3", 7),
+            ("v3", "t", 20),
+            ("v4", "t", 4),
+        ] {
+            network.add_edge(a, b, c);
+        }
+        network
+    }
+
+    #[test]
+    fn test_max_flow() {
+        let mut network = clrs();
+        assert_eq!(network.edmonds_karp(&"s", &"t"), 23);
+        network.reset();
+        assert_eq!(network.dinic(&"s", &"t"), 23);
+        assert_eq!(network.dinic(&"s", &"missing"), 0);
+        network.reset();
+        assert_eq!(network.edmonds_karp(&"v1", &"v1"), 0);
+        assert_eq!(network.dinic(&"v1", &"v1"), 0);
+    }
+
+    #[test]
+    fn test_min_cut() {
+        let cut = clrs().min_cut(&"s", &"t");
+        assert_eq!(cut.value, 23);
+        let mut edges = cut.edges.clone();
+        edges.sort();
+        assert_eq!(edges, vec![("v1", "v3"), ("v4", "t"), ("v4", "v3")]);
+        assert_eq!(cut.source_side, HashSet::from(["s", "v1", "v2", "v4"]));
+        assert_eq!(cut.sink_side, HashSet::from(["v3", "t"]));
+
+        let cut = clrs().min_cut(&"s", &"s");
+        assert_eq!(cut.value, 0);
+        assert!(cut.edges.is_empty());
+    }
+
+    #[test]
+    fn test_from_graph() {
+        // two disjoint paths from 1 to 4
+        let mut graph: Graph<i32> = HashMap::new();
+        for (a, b) in [(1, 2), (2, 4), (1, 3), (3, 4), (2, 3)] {
+            graph.entry(a).or_default().insert(b);
+        }
+        assert_eq!(FlowNetwork::from_graph(&graph).dinic(&1, &4), 2);
+    }
+
+    #[test]
+    fn test_hopcroft_karp() {
+        // allergens of 2020 day 21 once the candidates have been narrowed down
+        let edges: HashMap<&str, HashSet<&str>> = HashMap::from([
+            ("dairy", HashSet::from(["mxmxvkd"])),
+            ("fish", HashSet::from(["mxmxvkd", "sqjhc"])),
+            ("soy", HashSet::from(["sqjhc", "fvjkl"])),
+        ]);
+        let matching = hopcroft_karp(&edges);
+        assert_eq!(
+            matching,
+            HashMap::from([("dairy", "mxmxvkd"), ("fish", "sqjhc"), ("soy", "fvjkl")])
+        );
+
+        let crowded: HashMap<u8, HashSet<char>> = HashMap::from([
+            (1, HashSet::from(['a'])),
+            (2, HashSet::from(['a'])),
+            (3, HashSet::from(['a', 'b'])),
+        ]);
+        assert_eq!(hopcroft_karp(&crowded).len(), 2);
+    }
+
+    #[test]
+    fn test_stoer_wagner() {
+        // wiring diagram of 2023 day 25
+        let input = "jqt: rhn xhk nvd
+rsh: frs pzl lsr
+xhk: hfx
+cmg: qnr nvd lhk bvb
+rhn: xhk bvb hfx
+bvb: xhk hfx
+pzl: lsr hfx nvd
+qnr: nvd
+ntq: jqt hfx bvb xhk
+nvd: lhk
+lsr: lhk
+rzs: qnr cmg lsr rsh
+frs: qnr lhk lsr";
+        let edges: Vec<(&str, &str, i64)> = input
+            .lines()
+            .flat_map(|line| {
+                let (from, to) = line.split_once(": ").unwrap();
+                to.split(' ').map(move |t| (from, t, 1))
+            })
+            .collect();
+        let (cut, side) = stoer_wagner(&edges).unwrap();
+        assert_eq!(cut, 3);
+        assert_eq!(side.len() * (15 - side.len()), 54);
+        assert_eq!(stoer_wagner::<u8>(&[]), None);
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::HashSet;
+
+use crate::grid::ORTHOGONAL;
+use crate::math::gcd;
+use crate::parsing::Pos;
+
+/// Twice the area of a simple polygon, by the shoelace formula.
+///
+/// Doubling keeps the result integral; vertices may go either way around.
+pub fn shoelace_doubled(vertices: &[Pos<i64>]) -> i64 {
+    let n = vertices.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (vertices[i], vertices[(i + 1) % n]);
+            a.column * b.line - b.column * a.line
+        })
+        .sum::<i64>()
+        .abs()
+}
+
+/// Lattice points on the polygon edges.
+pub fn boundary_points(vertices: &[Pos<i64>]) -> i64 {
+    let n = vertices.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (vertices[i], vertices[(i + 1) % n]);
+            gcd(b.line - a.line, b.column - a.column)
+        })
+        .sum()
+}
+
+/// Lattice points strictly inside the polygon, by Pick's theorem
+/// `A = I + B / 2 - 1`.
+pub fn interior_points(vertices: &[Pos<i64>]) -> i64 {
+    (shoelace_doubled(vertices) - boundary_points(vertices) + 2) / 2
+}
+
+/// Grid cells covered by a loop drawn through cell centers, edges included.
+pub fn enclosed_cells(vertices: &[Pos<i64>]) -> i64 {
+    interior_points(vertices) + boundary_points(vertices)
+}
+
+/// Smallest lattice step going from `a` towards `b`, zero when they are the
+/// same point.
+pub fn lattice_step(a: Pos<i64>, b: Pos<i64>) -> Pos<i64> {
+    let (dl, dc) = (b.line - a.line, b.column - a.column);
+    let g = gcd(dl, dc).max(1);
+    Pos {
+        line: dl / g,
+        column: dc / g,
+    }
+}
+
+/// Every lattice point of the segment `a..=b`.
+pub fn segment_points(a: Pos<i64>, b: Pos<i64>) -> Vec<Pos<i64>> {
+    let step = lattice_step(a, b);
+    let count = gcd(b.line - a.line, b.column - a.column);
+    (0..=count)
+        .map(|k| Pos {
+            line: a.line + k * step.line,
+            column: a.column + k * step.column,
+        })
+        .collect()
+}
+
+/// Lattice points from `origin` moving by `step`, while `inside` holds.
+///
+/// `origin` itself comes first. A zero `step` goes nowhere, so yields only
+/// `origin`.
+pub fn ray_points<F>(origin: Pos<i64>, step: Pos<i64>, inside: F) -> impl Iterator<Item = Pos<i64>>
+where
+    F: Fn(Pos<i64>) -> bool,
+{
+    let moves = step.line != 0 || step.column != 0;
+    std::iter::successors(Some(origin), move |p| {
+        moves.then(|| Pos {
+            line: p.line + step.line,
+            column: p.column + step.column,
+        })
+    })
+    .take_while(move |p| inside(*p))
+}
+
+/// Every lattice point on the line through `a` and `b` within `lines x columns`,
+/// ordered in the direction from `a` to `b`. Either point may be out of bounds.
+///
+/// Two equal points make no line, only `a` itself if it is inside.
+pub fn line_points(a: Pos<i64>, b: Pos<i64>, lines: i64, columns: i64) -> Vec<Pos<i64>> {
+    let step = lattice_step(a, b);
+    if step.line == 0 && step.column == 0 {
+        let inside = (0..lines).contains(&a.line) && (0..columns).contains(&a.column);
+        return if inside { vec![a] } else { vec![] };
+    }
+    // the `k` for which `a + k * step` is inside, one axis after the other
+    let (Some((l_min, l_max)), Some((c_min, c_max))) = (
+        axis_range(a.line, step.line, lines),
+        axis_range(a.column, step.column, columns),
+    ) else {
+        return vec![];
+    };
+    (l_min.max(c_min)..=l_max.min(c_max))
+        .map(|k| Pos {
+            line: a.line + k * step.line,
+            column: a.column + k * step.column,
+        })
+        .collect()
+}
+
+/// The `k` with `start + k * step` in `0..len`, any `k` for a zero step.
+fn axis_range(start: i64, step: i64, len: i64) -> Option<(i64, i64)> {
+    if step == 0 {
+        return (0..len).contains(&start).then_some((i64::MIN, i64::MAX));
+    }
+    // `lo <= k * step <= hi`, turned around for a negative step
+    let (lo, hi) = (-start, len - 1 - start);
+    let (step, lo, hi) = if step > 0 {
+        (step, lo, hi)
+    } else {
+        (-step, -hi, -lo)
+    };
+    let (first, last) = (-(-lo).div_euclid(step), hi.div_euclid(step));
+    (first <= last).then_some((first, last))
+}
+
+/// Cells crossed by the straight line from `a` to `b`, by Bresenham.
+pub fn bresenham(a: Pos<i64>, b: Pos<i64>) -> Vec<Pos<i64>> {
+    let (dc, dl) = ((b.column - a.column).abs(), -(b.line - a.line).abs());
+    let (sc, sl) = ((b.column - a.column).signum(), (b.line - a.line).signum());
+    let mut err = dc + dl;
+    let mut p = a;
+    let mut cells = vec![p];
+    while p != b {
+        let e2 = 2 * err;
+        if e2 >= dl {
+            err += dl;
+            p.column += sc;
+        }
+        if e2 <= dc {
+            err += dc;
+            p.line += sl;
+        }
+        cells.push(p);
+    }
+    cells
+}
+
+/// True if `p` is inside the polygon or on its boundary.
+pub fn point_in_polygon(p: Pos<i64>, vertices: &[Pos<i64>]) -> bool {
+    let n = vertices.len();
+    let mut inside = false;
+    for i in 0..n {
+        let (a, b) = (vertices[i], vertices[(i + 1) % n]);
+        // on the edge: collinear and within the bounding box
+        let cross =
+            (b.column - a.column) * (p.line - a.line) - (b.line - a.line) * (p.column - a.column);
+        if cross == 0
+            && p.line >= a.line.min(b.line)
+            && p.line <= a.line.max(b.line)
+            && p.column >= a.column.min(b.column)
+            && p.column <= a.column.max(b.column)
+        {
+            return true;
+        }
+        // count crossings of a ray going right from p
+        if (a.line > p.line) != (b.line > p.line) {
+            let t = (p.line - a.line) as f64 / (b.line - a.line) as f64;
+            let x = a.column as f64 + t * (b.column - a.column) as f64;
+            if (p.column as f64) < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+fn offset(p: Pos<i64>, (dl, dc): (isize, isize)) -> Pos<i64> {
+    Pos {
+        line: p.line + dl as i64,
+        column: p.column + dc as i64,
+    }
+}
+
+/// Number of cell edges facing a cell outside the set.
+pub fn perimeter(cells: &HashSet<Pos<i64>>) -> usize {
+    cells
+        .iter()
+        .map(|&p| {
+            ORTHOGONAL
+                .iter()
+                .filter(|&&d| !cells.contains(&offset(p, d)))
+                .count()
+        })
+        .sum()
+}
+
+/// Number of straight sides of the shape, equal to its number of corners.
+pub fn side_count(cells: &HashSet<Pos<i64>>) -> usize {
+    cells
+        .iter()
+        .map(|&p| cell_corners(|d| cells.contains(&offset(p, d))))
+        .sum()
+}
+
+/// Corners of the shape at one of its cells, `has(d)` telling whether the
+/// neighbour at offset `d` is part of the shape too.
+pub fn cell_corners(has: impl Fn((isize, isize)) -> bool) -> usize {
+    // each pair of orthogonal directions around the cell
+    (0..4)
+        .filter(|&i| {
+            let (a, b) = (ORTHOGONAL[i], ORTHOGONAL[(i + 1) % 4]);
+            let (has_a, has_b) = (has(a), has(b));
+            let has_diag = has((a.0 + b.0, a.1 + b.1));
+            // convex corner, or concave corner seen from the inside
+            (!has_a && !has_b) || (has_a && has_b && !has_diag)
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(line: i64, column: i64) -> Pos<i64> {
+        Pos { line, column }
+    }
+
+    #[test]
+    fn test_area_and_pick() {
+        let square = [p(0, 0), p(0, 4), p(4, 4), p(4, 0)];
+        assert_eq!(shoelace_doubled(&square), 32);
+        assert_eq!(boundary_points(&square), 16);
+        assert_eq!(interior_points(&square), 9);
+        assert_eq!(enclosed_cells(&square), 25);
+
+        // dig plan of 2023 day 18
+        let plan = [
+            p(0, 0),
+            p(0, 6),
+            p(5, 6),
+            p(5, 4),
+            p(7, 4),
+            p(7, 6),
+            p(9, 6),
+            p(9, 1),
+            p(7, 1),
+            p(7, 0),
+            p(5, 0),
+            p(5, 2),
+            p(2, 2),
+            p(2, 0),
+        ];
+        assert_eq!(enclosed_cells(&plan), 62);
+    }
+
+    #[test]
+    fn test_lattice_lines() {
+        assert_eq!(lattice_step(p(0, 0), p(4, 6)), p(2, 3));
+        assert_eq!(
+            segment_points(p(0, 0), p(4, 6)),
+            vec![p(0, 0), p(2, 3), p(4, 6)]
+        );
+        assert_eq!(segment_points(p(1, 1), p(1, 1)), vec![p(1, 1)]);
+        // day 8 example, the T antennas resonate along the whole line
+        assert_eq!(
+            line_points(p(0, 0), p(1, 3), 10, 10),
+            vec![p(0, 0), p(1, 3), p(2, 6), p(3, 9)]
+        );
+        assert_eq!(
+            line_points(p(2, 2), p(4, 4), 5, 5),
+            vec![p(0, 0), p(1, 1), p(2, 2), p(3, 3), p(4, 4)]
+        );
+        // no direction to walk in
+        assert_eq!(lattice_step(p(2, 2), p(2, 2)), p(0, 0));
+        assert_eq!(ray_points(p(2, 2), p(0, 0), |_| true).count(), 1);
+        assert_eq!(line_points(p(2, 2), p(2, 2), 5, 5), vec![p(2, 2)]);
+        assert_eq!(line_points(p(7, 2), p(7, 2), 5, 5), vec![]);
+        // starting outside, the line still crosses the grid
+        assert_eq!(
+            line_points(p(-1, -1), p(0, 0), 5, 5),
+            vec![p(0, 0), p(1, 1), p(2, 2), p(3, 3), p(4, 4)]
+        );
+        assert_eq!(line_points(p(9, 1), p(7, 2), 5, 5), vec![p(3, 4)]);
+        assert_eq!(
+            line_points(p(4, 0), p(3, 1), 5, 5),
+            vec![p(4, 0), p(3, 1), p(2, 2), p(1, 3), p(0, 4)]
+        );
+        assert_eq!(line_points(p(9, 0), p(9, 1), 5, 5), vec![]);
+    }
+
+    #[test]
+    fn test_bresenham() {
+        assert_eq!(
+            bresenham(p(0, 0), p(2, 5)),
+            vec![p(0, 0), p(0, 1), p(1, 2), p(1, 3), p(2, 4), p(2, 5)]
+        );
+        assert_eq!(bresenham(p(3, 3), p(0, 3)).len(), 4);
+    }
+
+    #[test]
+    fn test_point_in_polygon() {
+        let l_shape = [p(0, 0), p(0, 4), p(2, 4), p(2, 2), p(4, 2), p(4, 0)];
+        assert!(point_in_polygon(p(1, 1), &l_shape));
+        assert!(point_in_polygon(p(3, 1), &l_shape));
+        assert!(!point_in_polygon(p(3, 3), &l_shape));
+        assert!(point_in_polygon(p(2, 3), &l_shape));
+        assert!(!point_in_polygon(p(5, 1), &l_shape));
+    }
+
+    #[test]
+    fn test_perimeter_and_sides() {
+        // the E shaped region of 2024 day 12
+        let e: HashSet<Pos<i64>> = "EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE"
+            .lines()
+            .enumerate()
+            .flat_map(|(l, row)| {
+                row.chars()
+                    .enumerate()
+                    .filter(|(_, ch)| *ch == 'E')
+                    .map(move |(c, _)| p(l as i64, c as i64))
+            })
+            .collect();
+        assert_eq!(e.len(), 17);
+        assert_eq!(perimeter(&e), 36);
+        assert_eq!(side_count(&e), 12);
+
+        let single: HashSet<Pos<i64>> = [p(0, 0)].into();
+        assert_eq!((perimeter(&single), side_count(&single)), (4, 4));
+    }
+}
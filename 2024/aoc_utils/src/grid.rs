@@ -0,0 +1,262 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Index, IndexMut};
+
+use crate::parsing::Pos;
+
+/// Offsets of the 4 orthogonal neighbors: up, right, down, left.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+/// Offsets of the 8 surrounding neighbors, clockwise from up.
+pub const SURROUNDING: [(isize, isize); 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// Dense rectangular grid stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid2D<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid2D<T> {
+    /// Grid of `height` lines of `width` cells, `cells` given row by row.
+    pub fn from_vec(width: usize, height: usize, cells: Vec<T>) -> Self {
+        assert_eq!(width * height, cells.len(), "cells don't fill the grid");
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    /// Grid of the puzzle input, one line per row, every char mapped by `cell_of`.
+    ///
+    /// Panics if the lines don't all have the same length.
+    pub fn parse_with<F>(input: &str, mut cell_of: F) -> Self
+    where
+        F: FnMut(char) -> T,
+    {
+        let mut width = 0;
+        let mut height = 0;
+        let mut cells = Vec::with_capacity(input.len());
+        for line in input.lines() {
+            let before = cells.len();
+            cells.extend(line.chars().map(&mut cell_of));
+            let len = cells.len() - before;
+            if height == 0 {
+                width = len;
+            }
+            assert_eq!(width, len, "line {height} isn't {width} wide");
+            height += 1;
+        }
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn cells(&self) -> &[T] {
+        &self.cells
+    }
+
+    pub fn cells_mut(&mut self) -> &mut [T] {
+        &mut self.cells
+    }
+
+    fn offset(&self, pos: Pos<usize>) -> Option<usize> {
+        (pos.line < self.height && pos.column < self.width)
+            .then_some(pos.line * self.width + pos.column)
+    }
+
+    pub fn pos_of(&self, offset: usize) -> Pos<usize> {
+        Pos {
+            line: offset / self.width,
+            column: offset % self.width,
+        }
+    }
+
+    pub fn get(&self, pos: Pos<usize>) -> Option<&T> {
+        self.offset(pos).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, pos: Pos<usize>) -> Option<&mut T> {
+        self.offset(pos).map(|i| &mut self.cells[i])
+    }
+
+    pub fn contains(&self, pos: Pos<usize>) -> bool {
+        self.offset(pos).is_some()
+    }
+
+    /// `pos` moved by `(line, column)`, if it stays on the grid.
+    pub fn step(&self, pos: Pos<usize>, (dl, dc): (isize, isize)) -> Option<Pos<usize>> {
+        let next = Pos {
+            line: pos.line.checked_add_signed(dl)?,
+            column: pos.column.checked_add_signed(dc)?,
+        };
+        self.contains(next).then_some(next)
+    }
+
+    /// Orthogonal neighbors of `pos` that are on the grid.
+    pub fn neighbors4(&self, pos: Pos<usize>) -> impl Iterator<Item = Pos<usize>> + '_ {
+        ORTHOGONAL.iter().filter_map(move |&d| self.step(pos, d))
+    }
+
+    /// Orthogonal and diagonal neighbors of `pos` that are on the grid.
+    pub fn neighbors8(&self, pos: Pos<usize>) -> impl Iterator<Item = Pos<usize>> + '_ {
+        SURROUNDING.iter().filter_map(move |&d| self.step(pos, d))
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Pos<usize>> + '_ {
+        (0..self.cells.len()).map(|i| self.pos_of(i))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Pos<usize>, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (self.pos_of(i), v))
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// First position whose cell matches.
+    pub fn find<F>(&self, mut pred: F) -> Option<Pos<usize>>
+    where
+        F: FnMut(&T) -> bool,
+    {
+        self.cells
+            .iter()
+            .position(&mut pred)
+            .map(|i| self.pos_of(i))
+    }
+
+    pub fn map<U, F>(&self, f: F) -> Grid2D<U>
+    where
+        F: FnMut(&T) -> U,
+    {
+        Grid2D {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// One line per row, every cell drawn by `glyph`.
+    pub fn render<F>(&self, mut glyph: F) -> String
+    where
+        F: FnMut(Pos<usize>, &T) -> char,
+    {
+        let mut out = String::with_capacity((self.width + 1) * self.height);
+        for (i, v) in self.cells.iter().enumerate() {
+            if i > 0 && i % self.width == 0 {
+                out.push('\n');
+            }
+            out.push(glyph(self.pos_of(i), v));
+        }
+        out
+    }
+}
+
+impl<T: Clone> Grid2D<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Self {
+        Self::from_vec(width, height, vec![fill; width * height])
+    }
+
+    /// Grid turned a quarter clockwise, the first line becoming the last column.
+    pub fn rotated(&self) -> Self {
+        let mut cells = Vec::with_capacity(self.cells.len());
+        for column in 0..self.width {
+            for line in (0..self.height).rev() {
+                cells.push(self.cells[line * self.width + column].clone());
+            }
+        }
+        Self::from_vec(self.height, self.width, cells)
+    }
+
+    /// Grid flipped left to right.
+    pub fn mirrored(&self) -> Self {
+        let cells = self
+            .rows()
+            .flat_map(|row| row.iter().rev().cloned())
+            .collect();
+        Self::from_vec(self.width, self.height, cells)
+    }
+}
+
+impl<T> Index<Pos<usize>> for Grid2D<T> {
+    type Output = T;
+
+    fn index(&self, pos: Pos<usize>) -> &T {
+        self.get(pos)
+            .unwrap_or_else(|| panic!("{pos} is outside the grid"))
+    }
+}
+
+impl<T> IndexMut<Pos<usize>> for Grid2D<T> {
+    fn index_mut(&mut self, pos: Pos<usize>) -> &mut T {
+        self.get_mut(pos)
+            .unwrap_or_else(|| panic!("{pos} is outside the grid"))
+    }
+}
+
+impl<T: Display> Display for Grid2D<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for row in self.rows() {
+            for v in row {
+                write!(f, "{v}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_neighbors() {
+        let grid = Grid2D::parse_with("abc\ndef", |ch| ch);
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[Pos { line: 1, column: 2 }], 'f');
+        assert_eq!(grid.get(Pos { line: 2, column: 0 }), None);
+
+        let corner: Vec<char> = grid
+            .neighbors4(Pos { line: 0, column: 0 })
+            .map(|p| grid[p])
+            .collect();
+        assert_eq!(corner, vec!['b', 'd']);
+        assert_eq!(grid.neighbors8(Pos { line: 1, column: 1 }).count(), 5);
+        assert_eq!(grid.find(|&c| c == 'e'), Some(Pos { line: 1, column: 1 }));
+        assert_eq!(grid.to_string(), "abc\ndef\n");
+        assert_eq!(grid.render(|_, c| c.to_ascii_uppercase()), "ABC\nDEF");
+    }
+
+    #[test]
+    fn test_rotate_and_mirror() {
+        let grid = Grid2D::parse_with("abc\ndef", |ch| ch);
+        assert_eq!(grid.rotated().to_string(), "da\neb\nfc\n");
+        assert_eq!(grid.mirrored().to_string(), "cba\nfed\n");
+        assert_eq!(grid.rotated().rotated().rotated().rotated(), grid);
+    }
+}
@@ -1,5 +1,23 @@
+pub mod automaton;
+pub mod bitset;
+pub mod cycle;
 pub mod display;
+pub mod fenwick;
+pub mod flow;
+pub mod geometry;
+pub mod grid;
+pub mod math;
+pub mod memo;
 pub mod parsing;
+pub mod paths;
+pub mod pattern;
+pub mod queue;
+pub mod ranges;
+pub mod regions;
+pub mod segtree;
+pub mod undirected;
 
 pub use display::*;
+pub use grid::*;
 pub use parsing::*;
+pub use ranges::*;
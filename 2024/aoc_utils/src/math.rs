@@ -0,0 +1,362 @@
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::ops::{Add, Div, Mul, Neg, Rem, Sub};
+
+/// Operations shared by every primitive integer type.
+///
+/// Only what the number-theory helpers need; the checked methods forward to
+/// the inherent ones of each primitive.
+pub trait Integer:
+    Copy
+    + Ord
+    + Hash
+    + Debug
+    + Display
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TEN: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    /// `None` for a zero divisor or `MIN / -1`.
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    /// `None` for a zero divisor or `MIN % -1`.
+    fn checked_rem(self, rhs: Self) -> Option<Self>;
+    fn checked_neg(self) -> Option<Self>;
+    fn checked_pow(self, exp: u32) -> Option<Self>;
+    /// Absolute value, `None` when it doesn't fit (`i32::MIN`...).
+    fn checked_abs(self) -> Option<Self>;
+    /// Floor of the square root, panics on negative values.
+    fn isqrt(self) -> Self;
+    /// Number of decimal digits, ignoring the sign. Zero has one digit.
+    fn digits(self) -> u32;
+}
+
+/// Integer types with negative values, needed by Bézout coefficients.
+pub trait SignedInteger: Integer + Neg<Output = Self> {}
+
+macro_rules! impl_integer {
+    ($t:ty, $abs:expr, $digits:expr) => {
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const TEN: Self = 10;
+
+            fn checked_add(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_add(self, rhs)
+            }
+            fn checked_sub(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_sub(self, rhs)
+            }
+            fn checked_mul(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_mul(self, rhs)
+            }
+            fn checked_div(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_div(self, rhs)
+            }
+            fn checked_rem(self, rhs: Self) -> Option<Self> {
+                <$t>::checked_rem(self, rhs)
+            }
+            fn checked_neg(self) -> Option<Self> {
+                <$t>::checked_neg(self)
+            }
+            fn checked_pow(self, exp: u32) -> Option<Self> {
+                <$t>::checked_pow(self, exp)
+            }
+            fn checked_abs(self) -> Option<Self> {
+                $abs(self)
+            }
+            fn isqrt(self) -> Self {
+                <$t>::isqrt(self)
+            }
+            fn digits(self) -> u32 {
+                $digits(self).checked_ilog10().map_or(1, |d| d + 1)
+            }
+        }
+    };
+    (unsigned $($t:ty),*) => {
+        $(impl_integer!($t, Some, std::convert::identity);)*
+    };
+    (signed $($t:ty),*) => {
+        $(
+            impl_integer!($t, <$t>::checked_abs, <$t>::unsigned_abs);
+            impl SignedInteger for $t {}
+        )*
+    };
+}
+
+impl_integer!(unsigned u8, u16, u32, u64, u128, usize);
+impl_integer!(signed i8, i16, i32, i64, i128, isize);
+
+/// Greatest common divisor, always non-negative. `gcd(0, 0) == 0`.
+///
+/// Panics when the gcd is `MIN` of a signed type, as for `gcd(i32::MIN, 0)`,
+/// see [`checked_gcd`].
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    checked_gcd(a, b).expect("gcd doesn't fit the type")
+}
+
+/// Same as [`gcd`], `None` when it doesn't fit the type.
+pub fn checked_gcd<T: Integer>(a: T, b: T) -> Option<T> {
+    let (mut a, mut b) = (a, b);
+    while b != T::ZERO {
+        // only `MIN % -1` overflows, and -1 divides everything
+        (a, b) = (b, a.checked_rem(b).unwrap_or(T::ZERO));
+    }
+    a.checked_abs()
+}
+
+/// Least common multiple, `None` on overflow.
+pub fn lcm<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO || b == T::ZERO {
+        return Some(T::ZERO);
+    }
+    (a / checked_gcd(a, b)?).checked_mul(b)?.checked_abs()
+}
+
+/// Greatest common divisor of every value, panicking like [`gcd`].
+pub fn gcd_all<T: Integer>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::ZERO, gcd)
+}
+
+/// Least common multiple of every value, `None` on overflow.
+pub fn lcm_all<T: Integer>(values: impl IntoIterator<Item = T>) -> Option<T> {
+    values.into_iter().try_fold(T::ONE, |acc, v| lcm(acc, v))
+}
+
+/// Extended Euclid: `(g, x, y)` with `a * x + b * y == g == gcd(a, b)`,
+/// `None` if a step overflows.
+pub fn extended_gcd<T: SignedInteger>(a: T, b: T) -> Option<(T, T, T)> {
+    // `old - q * new`, checked
+    let next = |old: T, q: T, new: T| old.checked_sub(q.checked_mul(new)?);
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (T::ONE, T::ZERO);
+    let (mut old_y, mut y) = (T::ZERO, T::ONE);
+    while r != T::ZERO {
+        let q = old_r.checked_div(r)?;
+        (old_r, r) = (r, next(old_r, q, r)?);
+        (old_x, x) = (x, next(old_x, q, x)?);
+        (old_y, y) = (y, next(old_y, q, y)?);
+    }
+    if old_r < T::ZERO {
+        Some((
+            old_r.checked_neg()?,
+            old_x.checked_neg()?,
+            old_y.checked_neg()?,
+        ))
+    } else {
+        Some((old_r, old_x, old_y))
+    }
+}
+
+/// `a mod m` in `0..m`, for a positive modulus.
+pub fn rem_euclid<T: Integer>(a: T, m: T) -> T {
+    let r = a % m;
+    if r < T::ZERO {
+        r + m
+    } else {
+        r
+    }
+}
+
+/// `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime.
+pub fn mod_inverse<T: SignedInteger>(a: T, m: T) -> Option<T> {
+    let (g, x, _) = extended_gcd(rem_euclid(a, m), m)?;
+    (g == T::ONE).then(|| rem_euclid(x, m))
+}
+
+/// `a + b mod m` without overflowing, for `a`, `b` in `0..m`.
+pub fn add_mod<T: Integer>(a: T, b: T, m: T) -> T {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+/// `a * b mod m` without overflowing, for `a`, `b` in `0..m`.
+pub fn mul_mod<T: Integer>(a: T, b: T, m: T) -> T {
+    if let Some(p) = a.checked_mul(b) {
+        return p % m;
+    }
+    // double and add, each partial sum stays below m
+    let two = T::ONE + T::ONE;
+    let (mut a, mut b, mut acc) = (a, b, T::ZERO);
+    while b > T::ZERO {
+        if b % two == T::ONE {
+            acc = add_mod(acc, a, m);
+        }
+        a = add_mod(a, a, m);
+        b = b / two;
+    }
+    acc
+}
+
+/// `base^exp mod m` by square and multiply.
+pub fn pow_mod<T: Integer>(base: T, exp: u64, m: T) -> T {
+    if m == T::ONE {
+        return T::ZERO;
+    }
+    let (mut base, mut exp, mut acc) = (rem_euclid(base, m), exp, T::ONE);
+    while exp > 0 {
+        if exp & 1 == 1 {
+            acc = mul_mod(acc, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    acc
+}
+
+/// Chinese remainder theorem over `(residue, modulus)` pairs.
+///
+/// Moduli don't need to be coprime. Returns `(x, lcm)` with `x` in `0..lcm`,
+/// or `None` if the congruences conflict or any step overflows.
+pub fn crt<T: SignedInteger>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    congruences
+        .iter()
+        .try_fold((T::ZERO, T::ONE), |(r1, m1), &(r2, m2)| {
+            let (g, p, _) = extended_gcd(m1, m2)?;
+            // reduced first, so residues far apart don't overflow
+            let diff = rem_euclid(r2, m2).checked_sub(r1)?;
+            if diff % g != T::ZERO {
+                return None;
+            }
+            let m = lcm(m1, m2)?;
+            // x = r1 + m1 * ((diff / g) * p mod m2 / g)
+            let step = m2 / g;
+            let k = mul_mod(rem_euclid(diff / g, step), rem_euclid(p, step), step);
+            let x = add_mod(rem_euclid(r1, m), mul_mod(m1, k, m), m);
+            Some((x, m))
+        })
+}
+
+/// Floor of the square root.
+pub fn isqrt<T: Integer>(n: T) -> T {
+    n.isqrt()
+}
+
+/// Number of decimal digits of `n`, ignoring the sign.
+pub fn digit_count<T: Integer>(n: T) -> u32 {
+    n.digits()
+}
+
+/// Decimal concatenation `a || b`, i.e. `a * 10^digits(b) + b`.
+///
+/// An empty prefix, zero, leaves `b` as is even when `10^digits(b)` doesn't
+/// fit.
+pub fn concat<T: Integer>(a: T, b: T) -> Option<T> {
+    if a == T::ZERO {
+        return Some(b);
+    }
+    a.checked_mul(T::TEN.checked_pow(b.digits())?)?
+        .checked_add(b)
+}
+
+/// True if the decimal writing of `n` ends with the one of `suffix`.
+pub fn ends_with_digits<T: Integer>(n: T, suffix: T) -> bool {
+    strip_digits(n, suffix).is_some()
+}
+
+/// Inverse of [`concat`]: the prefix `a` with `a || suffix == n`.
+///
+/// A number that is only its suffix has an empty prefix, read as zero.
+pub fn strip_digits<T: Integer>(n: T, suffix: T) -> Option<T> {
+    if n < T::ZERO || suffix < T::ZERO || n < suffix {
+        return None;
+    }
+    let Some(pow) = T::TEN.checked_pow(suffix.digits()) else {
+        // suffix is as wide as the type, only itself ends with it
+        return (n == suffix).then_some(T::ZERO);
+    };
+    ((n - suffix) % pow == T::ZERO).then(|| (n - suffix) / pow)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd_lcm() {
+        assert_eq!(gcd(12u32, 18), 6);
+        assert_eq!(gcd(-12i64, 18), 6);
+        assert_eq!(gcd(0u8, 0), 0);
+        assert_eq!(gcd(i32::MIN, -1), 1);
+        assert_eq!(gcd(i32::MIN, 6), 2);
+        assert_eq!(checked_gcd(i32::MIN, 0), None);
+        assert_eq!(checked_gcd(i32::MIN, i32::MIN), None);
+        assert_eq!(checked_gcd(i32::MIN, 4), Some(4));
+        assert_eq!(lcm(i32::MIN, i32::MIN), None);
+        assert_eq!(lcm(i32::MIN, 1), None);
+        assert_eq!(lcm_all([i32::MIN, i32::MIN]), None);
+        assert_eq!(lcm(4i32, -6), Some(12));
+        assert_eq!(lcm(u64::MAX, u64::MAX - 1), None);
+        assert_eq!(gcd_all([12, 18, 30]), 6);
+        assert_eq!(lcm_all(1u64..=20), Some(232_792_560));
+    }
+
+    #[test]
+    fn test_extended_gcd_and_inverse() {
+        let (g, x, y) = extended_gcd(240i64, 46).unwrap();
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        assert_eq!(extended_gcd(i32::MIN, -1), None);
+        assert_eq!(extended_gcd(i32::MIN, 0), None);
+        assert_eq!(mod_inverse(3i32, 11), Some(4));
+        assert_eq!(mod_inverse(-3i32, 11), Some(7));
+        assert_eq!(mod_inverse(6i32, 9), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // non coprime but consistent, then inconsistent
+        assert_eq!(crt(&[(2i64, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1i64, 4), (2, 6)]), None);
+        // residues at both ends of the type, then an lcm past it
+        assert_eq!(crt(&[(i64::MAX, 3), (i64::MIN, 5)]), Some((7, 15)));
+        assert_eq!(crt(&[(1i64, i64::MAX), (0, i64::MAX - 1)]), None);
+        // bus schedule from 2020 day 13: 17,x,13,19 -> t + i ≡ 0 (mod bus)
+        assert_eq!(
+            crt(&[(0i64, 17), (-2, 13), (-3, 19)]),
+            Some((3417, 17 * 13 * 19))
+        );
+    }
+
+    #[test]
+    fn test_pow_mod() {
+        assert_eq!(pow_mod(4u32, 13, 497), 445);
+        assert_eq!(pow_mod(2u64, 10, 1), 0);
+        // products overflow u64 and go through double and add
+        let m = u64::MAX - 58; // largest 64 bit prime
+        assert_eq!(pow_mod(3u64, m - 1, m), 1);
+    }
+
+    #[test]
+    fn test_digits() {
+        assert_eq!(isqrt(99u64), 9);
+        assert_eq!(isqrt(100i32), 10);
+        assert_eq!(digit_count(0u8), 1);
+        assert_eq!(digit_count(-1234i16), 4);
+        assert_eq!(digit_count(u64::MAX), 20);
+        assert_eq!(concat(15u64, 6), Some(156));
+        assert_eq!(concat(12u64, 0), Some(120));
+        assert_eq!(concat(u64::MAX / 10, 99), None);
+        assert_eq!(concat(0, u64::MAX), Some(u64::MAX));
+        assert!(ends_with_digits(156u64, 56));
+        assert!(!ends_with_digits(156u64, 57));
+        assert_eq!(strip_digits(156u64, 6), Some(15));
+        assert_eq!(strip_digits(156u64, 156), Some(0));
+        assert_eq!(strip_digits(6u64, 16), None);
+        assert_eq!(strip_digits(100u8, 0), Some(10));
+        assert_eq!(strip_digits(u64::MAX, u64::MAX), Some(0));
+        assert_eq!(strip_digits(u64::MAX, u64::MAX - 1), None);
+    }
+}
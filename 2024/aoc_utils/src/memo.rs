@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Cache for recursive solutions, keyed by a projection of the argument.
+///
+/// The recursive function receives a `recurse` callback instead of calling
+/// itself, so it can be written as a plain closure:
+///
+/// ```
+/// use aoc_utils::memo::Memo;
+///
+/// let mut memo = Memo::new();
+/// let fib = |recurse: &mut dyn FnMut(u64) -> u64, n: u64| {
+///     if n < 2 { n } else { recurse(n - 1) + recurse(n - 2) }
+/// };
+/// assert_eq!(memo.get(90, &fib), 2_880_067_194_370_816_120);
+/// ```
+pub struct Memo<A, K, V> {
+    cache: HashMap<K, V>,
+    key_of: fn(&A) -> K,
+    stats: MemoStats,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl<K, V> Memo<K, K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+{
+    /// Memo keyed by the whole argument.
+    pub fn new() -> Self {
+        Self::with_key(K::clone)
+    }
+}
+
+impl<K, V> Default for Memo<K, K, V>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<A, K, V> Memo<A, K, V>
+where
+    K: Eq + Hash,
+    V: Clone,
+{
+    /// Memo keyed by `key_of(arg)`, for arguments carrying data that doesn't
+    /// change the result (a shared grid, a path so far...).
+    pub fn with_key(key_of: fn(&A) -> K) -> Self {
+        Self {
+            cache: HashMap::new(),
+            key_of,
+            stats: MemoStats::default(),
+        }
+    }
+
+    /// Cached value of `f(arg)`, computing and storing it on a miss.
+    pub fn get<F>(&mut self, arg: A, f: &F) -> V
+    where
+        F: Fn(&mut dyn FnMut(A) -> V, A) -> V,
+    {
+        let key = (self.key_of)(&arg);
+        if let Some(v) = self.cache.get(&key) {
+            self.stats.hits += 1;
+            return v.clone();
+        }
+        self.stats.misses += 1;
+        let v = f(&mut |a| self.get(a, f), arg);
+        self.cache.insert(key, v.clone());
+        v
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Forgets every cached value, e.g. between two puzzle inputs.
+    ///
+    /// The statistics of the finished run are traced, then reset.
+    pub fn clear(&mut self) {
+        self.trace_stats();
+        self.cache.clear();
+        self.stats = MemoStats::default();
+    }
+
+    pub fn trace_stats(&self) {
+        tracing::debug!(
+            hits = self.stats.hits,
+            misses = self.stats.misses,
+            entries = self.cache.len(),
+            "memo stats"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fib_stats() {
+        let mut memo = Memo::new();
+        let fib = |recurse: &mut dyn FnMut(u64) -> u64, n: u64| {
+            if n < 2 {
+                n
+            } else {
+                recurse(n - 1) + recurse(n - 2)
+            }
+        };
+        assert_eq!(memo.get(50, &fib), 12_586_269_025);
+        // every n in 0..=50 is computed once, n - 2 is then found for n >= 3
+        assert_eq!(
+            memo.stats(),
+            MemoStats {
+                hits: 48,
+                misses: 51
+            }
+        );
+
+        memo.clear();
+        assert!(memo.is_empty());
+        assert_eq!(memo.get(10, &fib), 55);
+        assert_eq!(memo.stats().misses, 11);
+    }
+
+    #[test]
+    fn test_key_projection() {
+        // count trails climbing 0..=9 on the day 10 example, keyed on position only
+        let grid: Vec<Vec<u8>> = "89010123
+78121874
+87430965
+96549874
+45678903
+32019012
+01329801
+10456732"
+            .lines()
+            .map(|l| l.bytes().map(|b| b - b'0').collect())
+            .collect();
+
+        // (line, column, steps taken so far)
+        type Arg = (usize, usize, u32);
+        let mut memo: Memo<Arg, (usize, usize), u64> = Memo::with_key(|&(l, c, _)| (l, c));
+        let rating = |recurse: &mut dyn FnMut(Arg) -> u64, (l, c, steps): Arg| {
+            if grid[l][c] == 9 {
+                assert_eq!(steps, 9);
+                return 1;
+            }
+            let mut sum = 0;
+            for (dl, dc) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                let (nl, nc) = (l.wrapping_add_signed(dl), c.wrapping_add_signed(dc));
+                if grid.get(nl).and_then(|row| row.get(nc)) == Some(&(grid[l][c] + 1)) {
+                    sum += recurse((nl, nc, steps + 1));
+                }
+            }
+            sum
+        };
+
+        let mut total = 0;
+        for (l, row) in grid.iter().enumerate() {
+            for (c, &height) in row.iter().enumerate() {
+                if height == 0 {
+                    total += memo.get((l, c, 0), &rating);
+                }
+            }
+        }
+        assert_eq!(total, 81);
+        assert!(memo.stats().hits > 0);
+    }
+}
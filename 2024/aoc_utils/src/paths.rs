@@ -0,0 +1,370 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+use crate::grid::Grid2D;
+use crate::parsing::Pos;
+use crate::queue::MinQueue;
+
+/// Steps needed to reach every cell from the closest of `sources`.
+///
+/// `can_step(from, to)` tells if the move between two adjacent cells is
+/// allowed, e.g. `|_, to| *to != '#'` for a maze or `|a, b| *b == *a + 1`
+/// for day 10's hiking trails. Unreachable cells stay `None`.
+pub fn distance_field<T, F>(
+    grid: &Grid2D<T>,
+    sources: impl IntoIterator<Item = Pos<usize>>,
+    can_step: F,
+) -> Grid2D<Option<u32>>
+where
+    F: Fn(&T, &T) -> bool,
+{
+    let mut dist = Grid2D::new(grid.width(), grid.height(), None);
+    let mut queue = VecDeque::new();
+    for source in sources {
+        if grid.contains(source) && dist[source].is_none() {
+            dist[source] = Some(0);
+            queue.push_back(source);
+        }
+    }
+
+    while let Some(pos) = queue.pop_front() {
+        let d = dist[pos].expect("queued cells have a distance");
+        for next in grid.neighbors4(pos) {
+            if dist[next].is_none() && can_step(&grid[pos], &grid[next]) {
+                dist[next] = Some(d + 1);
+                queue.push_back(next);
+            }
+        }
+    }
+    dist
+}
+
+/// Shortest path lengths between every pair of nodes `0..n`, with the
+/// predecessor matrix needed to rebuild the paths.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceMatrix {
+    n: usize,
+    dist: Vec<Option<i64>>,
+    /// `prev[from * n + to]` is the node before `to` on the path from `from`.
+    prev: Vec<Option<usize>>,
+}
+
+impl DistanceMatrix {
+    fn unconnected(n: usize) -> Self {
+        let mut matrix = Self {
+            n,
+            dist: vec![None; n * n],
+            prev: vec![None; n * n],
+        };
+        for i in 0..n {
+            matrix.dist[i * n + i] = Some(0);
+        }
+        matrix
+    }
+
+    pub fn len(&self) -> usize {
+        self.n
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.n == 0
+    }
+
+    pub fn dist(&self, from: usize, to: usize) -> Option<i64> {
+        self.dist[from * self.n + to]
+    }
+
+    /// Nodes of a shortest path, both ends included.
+    pub fn path(&self, from: usize, to: usize) -> Option<Vec<usize>> {
+        self.dist(from, to)?;
+        let mut path = vec![to];
+        let mut current = to;
+        while current != from {
+            current = self.prev[from * self.n + current]?;
+            path.push(current);
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// All pairs shortest paths by Floyd–Warshall, for small dense graphs.
+///
+/// `edges` are directed `(from, to, weight)` over nodes `0..n`. Returns `None`
+/// if there is a negative cycle.
+pub fn floyd_warshall(n: usize, edges: &[(usize, usize, i64)]) -> Option<DistanceMatrix> {
+    let mut m = DistanceMatrix::unconnected(n);
+    for &(a, b, w) in edges {
+        if m.dist[a * n + b].is_none_or(|d| w < d) {
+            m.dist[a * n + b] = Some(w);
+            m.prev[a * n + b] = Some(a);
+        }
+    }
+
+    for k in 0..n {
+        for i in 0..n {
+            let Some(ik) = m.dist[i * n + k] else {
+                continue;
+            };
+            for j in 0..n {
+                let Some(kj) = m.dist[k * n + j] else {
+                    continue;
+                };
+                if m.dist[i * n + j].is_none_or(|d| ik + kj < d) {
+                    m.dist[i * n + j] = Some(ik + kj);
+                    m.prev[i * n + j] = m.prev[k * n + j];
+                }
+            }
+        }
+    }
+
+    (0..n).all(|i| m.dist[i * n + i] >= Some(0)).then_some(m)
+}
+
+/// All pairs shortest paths by Johnson, for large sparse graphs.
+///
+/// Bellman–Ford reweights the edges so they are non negative, then a
+/// Dijkstra runs from every node. Returns `None` on a negative cycle.
+pub fn johnson(n: usize, edges: &[(usize, usize, i64)]) -> Option<DistanceMatrix> {
+    // 1) potentials from a virtual node linked to everyone with weight 0
+    let mut potential = vec![0i64; n];
+    for round in 0..=n {
+        let mut changed = false;
+        for &(a, b, w) in edges {
+            if potential[a] + w < potential[b] {
+                potential[b] = potential[a] + w;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+        if round == n {
+            return None;
+        }
+    }
+
+    // 2) reweighted adjacency, every weight is now >= 0
+    let mut adjacency: Vec<Vec<(usize, i64)>> = vec![Vec::new(); n];
+    for &(a, b, w) in edges {
+        adjacency[a].push((b, w + potential[a] - potential[b]));
+    }
+
+    // 3) one Dijkstra per source, distances translated back
+    let mut m = DistanceMatrix::unconnected(n);
+    for source in 0..n {
+        let (dist, prev) = dijkstra(&adjacency, source);
+        for target in 0..n {
+            m.dist[source * n + target] =
+                dist[target].map(|d| d - potential[source] + potential[target]);
+            m.prev[source * n + target] = prev[target];
+        }
+    }
+    Some(m)
+}
+
+/// Single source shortest paths over non negative weights.
+pub fn dijkstra(
+    adjacency: &[Vec<(usize, i64)>],
+    source: usize,
+) -> (Vec<Option<i64>>, Vec<Option<usize>>) {
+    let mut dist = vec![None; adjacency.len()];
+    let mut prev = vec![None; adjacency.len()];
+    let mut heap = BinaryHeap::from([Reverse((0, source))]);
+    dist[source] = Some(0);
+
+    while let Some(Reverse((d, u))) = heap.pop() {
+        if dist[u].is_some_and(|best| d > best) {
+            continue;
+        }
+        for &(v, w) in &adjacency[u] {
+            if dist[v].is_none_or(|best| d + w < best) {
+                dist[v] = Some(d + w);
+                prev[v] = Some(u);
+                heap.push(Reverse((d + w, v)));
+            }
+        }
+    }
+    (dist, prev)
+}
+
+/// Costs found by [`dijkstra_by`], and the tree of paths reaching them.
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<K> {
+    pub dist: HashMap<K, u64>,
+    prev: HashMap<K, K>,
+    /// First goal state settled, if the search stopped on one.
+    pub goal: Option<K>,
+}
+
+impl<K> ShortestPaths<K>
+where
+    K: Clone + Eq + Hash,
+{
+    pub fn cost(&self, key: &K) -> Option<u64> {
+        self.dist.get(key).copied()
+    }
+
+    /// States of a cheapest path to `key`, from a source to `key` included.
+    pub fn path_to(&self, key: &K) -> Option<Vec<K>> {
+        self.dist.get(key)?;
+        let mut path = vec![key.clone()];
+        while let Some(prev) = self.prev.get(path.last()?) {
+            path.push(prev.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Dijkstra over any hashable state, e.g. `(position, heading)`.
+///
+/// `neighbors(state)` lists the next states with the cost to reach them. The
+/// search stops at the first settled state for which `is_goal` holds, or once
+/// everything reachable is settled. `queue` is an empty [`MinQueue`]: an
+/// [`IndexedHeap`](crate::queue::IndexedHeap) in general, a
+/// [`BucketQueue`](crate::queue::BucketQueue) when costs are small integers.
+pub fn dijkstra_by<K, Q, N, I, G>(
+    mut queue: Q,
+    sources: impl IntoIterator<Item = K>,
+    mut neighbors: N,
+    mut is_goal: G,
+) -> ShortestPaths<K>
+where
+    K: Clone + Eq + Hash,
+    Q: MinQueue<K>,
+    N: FnMut(&K) -> I,
+    I: IntoIterator<Item = (K, u64)>,
+    G: FnMut(&K) -> bool,
+{
+    let mut paths = ShortestPaths {
+        dist: HashMap::new(),
+        prev: HashMap::new(),
+        goal: None,
+    };
+    for source in sources {
+        paths.dist.insert(source.clone(), 0);
+        queue.push(source, 0);
+    }
+
+    while let Some((state, cost)) = queue.pop() {
+        if is_goal(&state) {
+            paths.goal = Some(state);
+            break;
+        }
+        for (next, step) in neighbors(&state) {
+            let next_cost = cost + step;
+            if paths.dist.get(&next).is_none_or(|&best| next_cost < best) {
+                paths.dist.insert(next.clone(), next_cost);
+                paths.prev.insert(next.clone(), state.clone());
+                queue.push(next, next_cost);
+            }
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_distance_field() {
+        let maze = Grid2D::parse_with("S.#\n..#\n#..", |ch| ch);
+        let dist = distance_field(&maze, [Pos { line: 0, column: 0 }], |_, to| *to != '#');
+        assert_eq!(
+            dist.render(|_, d| d.map_or('#', |d| char::from_digit(d, 10).unwrap())),
+            "01#\n12#\n#34"
+        );
+
+        // several sources, and a climbing rule from day 10
+        let hills = Grid2D::parse_with("0123\n9854\n0167", |ch| ch.to_digit(10).unwrap());
+        let sources = hills.positions().filter(|p| hills[*p] == 0);
+        let dist = distance_field(&hills, sources, |a, b| *b == a + 1);
+        assert_eq!(dist[Pos { line: 1, column: 3 }], Some(4));
+        assert_eq!(dist[Pos { line: 2, column: 1 }], Some(1));
+        assert_eq!(dist[Pos { line: 2, column: 3 }], Some(7));
+        assert_eq!(dist[Pos { line: 1, column: 1 }], None);
+    }
+
+    const REINDEER_MAZE: &str = "###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############";
+
+    fn reindeer_race<Q: MinQueue<(Pos<usize>, usize)>>(queue: Q) -> (u64, usize) {
+        use crate::grid::ORTHOGONAL;
+
+        let maze = Grid2D::parse_with(REINDEER_MAZE, |ch| ch);
+        let start = maze.find(|&c| c == 'S').unwrap();
+        // state is (position, index of the heading in ORTHOGONAL), east first
+        let paths = dijkstra_by(
+            queue,
+            [(start, 1)],
+            |&(pos, heading)| {
+                let ahead = maze
+                    .step(pos, ORTHOGONAL[heading])
+                    .filter(|&p| maze[p] != '#')
+                    .map(|p| ((p, heading), 1));
+                let turns = [1, 3].map(|t| ((pos, (heading + t) % 4), 1000));
+                ahead.into_iter().chain(turns)
+            },
+            |&(pos, _)| maze[pos] == 'E',
+        );
+        let goal = paths.goal.expect("the end is reachable");
+        let path = paths.path_to(&goal).unwrap();
+        assert_eq!(path[0], (start, 1));
+        (paths.cost(&goal).unwrap(), path.len())
+    }
+
+    #[test]
+    fn test_dijkstra_by_with_both_queues() {
+        use crate::queue::{BucketQueue, IndexedHeap};
+
+        let heap = reindeer_race(IndexedHeap::new());
+        let buckets = reindeer_race(BucketQueue::new());
+        assert_eq!(heap.0, 7036);
+        assert_eq!(heap, buckets);
+    }
+
+    fn sample() -> Vec<(usize, usize, i64)> {
+        vec![(0, 2, -2), (2, 3, 2), (3, 1, -1), (1, 0, 4), (1, 2, 3)]
+    }
+
+    #[test]
+    fn test_all_pairs_agree() {
+        let fw = floyd_warshall(4, &sample()).unwrap();
+        let jo = johnson(4, &sample()).unwrap();
+        for i in 0..4 {
+            for j in 0..4 {
+                assert_eq!(fw.dist(i, j), jo.dist(i, j), "{i} -> {j}");
+            }
+        }
+        assert_eq!(fw.dist(0, 1), Some(-1));
+        assert_eq!(fw.dist(1, 3), Some(4));
+        assert_eq!(fw.path(0, 1), Some(vec![0, 2, 3, 1]));
+        assert_eq!(jo.path(0, 1), Some(vec![0, 2, 3, 1]));
+        assert_eq!(fw.path(2, 2), Some(vec![2]));
+    }
+
+    #[test]
+    fn test_unreachable_and_negative_cycle() {
+        let m = johnson(3, &[(0, 1, 5)]).unwrap();
+        assert_eq!(m.dist(1, 0), None);
+        assert_eq!(m.path(1, 0), None);
+        assert_eq!(floyd_warshall(2, &[(0, 1, 1), (1, 0, -2)]), None);
+        assert_eq!(johnson(2, &[(0, 1, 1), (1, 0, -2)]), None);
+    }
+}
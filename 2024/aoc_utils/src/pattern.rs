@@ -0,0 +1,136 @@
+use crate::grid::{Grid2D, SURROUNDING};
+use crate::parsing::Pos;
+
+/// Every place `word` can be read in a straight line, as its first cell and
+/// the direction it goes, among the 8 of [`SURROUNDING`].
+///
+/// Matches may overlap, and a palindrome is found once per reading direction.
+pub fn find_word<T: PartialEq>(grid: &Grid2D<T>, word: &[T]) -> Vec<(Pos<usize>, (isize, isize))> {
+    let Some(first) = word.first() else {
+        return Vec::new();
+    };
+    let mut found = Vec::new();
+    for (start, cell) in grid.iter() {
+        if cell != first {
+            continue;
+        }
+        for &d in &SURROUNDING {
+            let mut pos = start;
+            let spelled = word[1..].iter().all(|letter| match grid.step(pos, d) {
+                Some(next) if grid[next] == *letter => {
+                    pos = next;
+                    true
+                }
+                _ => false,
+            });
+            if spelled {
+                found.push((start, d));
+            }
+        }
+    }
+    found
+}
+
+/// Kernel of `input` where `wildcard` matches any cell.
+pub fn parse_kernel(input: &str, wildcard: char) -> Grid2D<Option<char>> {
+    Grid2D::parse_with(input, |ch| (ch != wildcard).then_some(ch))
+}
+
+/// The distinct rotations and reflections of `kernel`, itself first.
+pub fn orientations<T: Clone + PartialEq>(kernel: &Grid2D<T>) -> Vec<Grid2D<T>> {
+    let mut all: Vec<Grid2D<T>> = Vec::with_capacity(8);
+    let mut turned = kernel.clone();
+    for _ in 0..4 {
+        for candidate in [turned.clone(), turned.mirrored()] {
+            if !all.contains(&candidate) {
+                all.push(candidate);
+            }
+        }
+        turned = turned.rotated();
+    }
+    all
+}
+
+/// True if `kernel` laid with its top left corner on `corner` fits the grid
+/// and agrees with every non wildcard cell.
+pub fn matches_at<T: PartialEq>(
+    grid: &Grid2D<T>,
+    kernel: &Grid2D<Option<T>>,
+    corner: Pos<usize>,
+) -> bool {
+    kernel.iter().all(|(offset, expected)| {
+        let pos = Pos {
+            line: corner.line + offset.line,
+            column: corner.column + offset.column,
+        };
+        match (expected, grid.get(pos)) {
+            (_, None) => false,
+            (None, Some(_)) => true,
+            (Some(expected), Some(cell)) => cell == expected,
+        }
+    })
+}
+
+/// Every place where some orientation of `kernel` matches, as the top left
+/// corner and the index of the orientation in [`orientations`].
+pub fn find_kernel<T: Clone + PartialEq>(
+    grid: &Grid2D<T>,
+    kernel: &Grid2D<Option<T>>,
+) -> Vec<(Pos<usize>, usize)> {
+    let variants = orientations(kernel);
+    grid.positions()
+        .flat_map(|corner| {
+            variants
+                .iter()
+                .enumerate()
+                .filter(move |(_, k)| matches_at(grid, k, corner))
+                .map(move |(i, _)| (corner, i))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_word() {
+        let grid = Grid2D::parse_with("XMAS\nMM..\nA.A.\nS..S", |ch| ch);
+        let word: Vec<char> = "XMAS".chars().collect();
+        let mut found = find_word(&grid, &word);
+        found.sort();
+        let origin = Pos { line: 0, column: 0 };
+        assert_eq!(
+            found,
+            vec![(origin, (0, 1)), (origin, (1, 0)), (origin, (1, 1))]
+        );
+
+        // both reading directions of a palindrome
+        let line = Grid2D::parse_with("abba", |ch| ch);
+        assert_eq!(find_word(&line, &['b', 'b']).len(), 2);
+        assert!(find_word(&line, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_orientations() {
+        // fully symmetric, a single orientation
+        assert_eq!(orientations(&parse_kernel("#.#\n.#.\n#.#", '?')).len(), 1);
+        // the X-MAS cross reads 4 ways
+        assert_eq!(orientations(&parse_kernel("M.S\n.A.\nM.S", '.')).len(), 4);
+        // an L tromino's reflections are also rotations, not the F pentomino's
+        assert_eq!(orientations(&parse_kernel("#.\n##", '?')).len(), 4);
+        assert_eq!(orientations(&parse_kernel(".##\n##.\n.#.", '?')).len(), 8);
+    }
+
+    #[test]
+    fn test_find_kernel() {
+        let grid = Grid2D::parse_with("M.S.M\n.A.A.\nM.S.M", |ch| ch);
+        let kernel = parse_kernel("M.S\n.A.\nM.S", '.');
+        let found = find_kernel(&grid, &kernel);
+        assert_eq!(found.len(), 2);
+        assert_eq!(found[0].0, Pos { line: 0, column: 0 });
+        assert_eq!(found[1].0, Pos { line: 0, column: 2 });
+        assert_ne!(found[0].1, found[1].1);
+        assert!(!matches_at(&grid, &kernel, Pos { line: 1, column: 0 }));
+    }
+}
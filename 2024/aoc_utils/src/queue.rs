@@ -0,0 +1,308 @@
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// Min priority queue with decrease-key, what [`crate::paths::dijkstra_by`]
+/// needs from its frontier.
+pub trait MinQueue<K> {
+    /// Queues `key`, or lowers its priority if it is already queued.
+    ///
+    /// Returns false, changing nothing, if `key` is queued at `priority` or
+    /// lower.
+    fn push(&mut self, key: K, priority: u64) -> bool;
+    /// Removes a key of least priority.
+    fn pop(&mut self) -> Option<(K, u64)>;
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Binary min-heap that knows where every key sits, so a queued key can be
+/// found and moved up in `O(log n)` instead of pushed again.
+#[derive(Debug, Clone)]
+pub struct IndexedHeap<K, P> {
+    heap: Vec<(P, K)>,
+    index: HashMap<K, usize>,
+}
+
+impl<K, P> Default for IndexedHeap<K, P> {
+    fn default() -> Self {
+        Self {
+            heap: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+}
+
+impl<K, P> IndexedHeap<K, P>
+where
+    K: Clone + Eq + Hash,
+    P: Copy + Ord,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.index.contains_key(key)
+    }
+
+    pub fn priority(&self, key: &K) -> Option<P> {
+        self.index.get(key).map(|&i| self.heap[i].0)
+    }
+
+    pub fn peek(&self) -> Option<(&K, P)> {
+        self.heap.first().map(|(p, k)| (k, *p))
+    }
+
+    /// Queues `key`, or lowers its priority if it is already queued.
+    ///
+    /// Returns false if `key` is already queued at `priority` or lower.
+    pub fn push(&mut self, key: K, priority: P) -> bool {
+        match self.index.get(&key) {
+            Some(&i) if self.heap[i].0 <= priority => false,
+            Some(&i) => {
+                self.heap[i].0 = priority;
+                self.sift_up(i);
+                true
+            }
+            None => {
+                self.index.insert(key.clone(), self.heap.len());
+                self.heap.push((priority, key));
+                self.sift_up(self.heap.len() - 1);
+                true
+            }
+        }
+    }
+
+    pub fn pop(&mut self) -> Option<(K, P)> {
+        if self.heap.is_empty() {
+            return None;
+        }
+        let last = self.heap.len() - 1;
+        self.swap(0, last);
+        let (priority, key) = self.heap.pop()?;
+        self.index.remove(&key);
+        if !self.heap.is_empty() {
+            self.sift_down(0);
+        }
+        Some((key, priority))
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.heap.swap(a, b);
+        for i in [a, b] {
+            *self
+                .index
+                .get_mut(&self.heap[i].1)
+                .expect("heap keys are indexed") = i;
+        }
+    }
+
+    fn sift_up(&mut self, mut i: usize) {
+        while i > 0 {
+            let parent = (i - 1) / 2;
+            if self.heap[parent].0 <= self.heap[i].0 {
+                break;
+            }
+            self.swap(i, parent);
+            i = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut i: usize) {
+        loop {
+            let smallest = [2 * i + 1, 2 * i + 2]
+                .into_iter()
+                .filter(|&c| c < self.heap.len())
+                .fold(i, |best, c| {
+                    if self.heap[c].0 < self.heap[best].0 {
+                        c
+                    } else {
+                        best
+                    }
+                });
+            if smallest == i {
+                break;
+            }
+            self.swap(i, smallest);
+            i = smallest;
+        }
+    }
+}
+
+impl<K> MinQueue<K> for IndexedHeap<K, u64>
+where
+    K: Clone + Eq + Hash,
+{
+    fn push(&mut self, key: K, priority: u64) -> bool {
+        IndexedHeap::push(self, key, priority)
+    }
+
+    fn pop(&mut self) -> Option<(K, u64)> {
+        IndexedHeap::pop(self)
+    }
+
+    fn len(&self) -> usize {
+        IndexedHeap::len(self)
+    }
+}
+
+/// Monotone bucket queue (Dial's algorithm) for small integer priorities.
+///
+/// There is one bucket per priority from the last popped one, so pushes and
+/// pops are `O(1)` amortized as long as edge weights stay small. A key moved
+/// to a lower bucket leaves a stale copy behind, skipped when reached.
+///
+/// Priorities must never be lower than the last popped one, which holds for
+/// Dijkstra with non negative weights.
+#[derive(Debug, Clone)]
+pub struct BucketQueue<K> {
+    /// `buckets[i]` holds the keys queued at `base + i`.
+    buckets: VecDeque<Vec<K>>,
+    base: u64,
+    /// Priority of the last popped key.
+    floor: u64,
+    queued: HashMap<K, u64>,
+}
+
+impl<K> Default for BucketQueue<K> {
+    fn default() -> Self {
+        Self {
+            buckets: VecDeque::new(),
+            base: 0,
+            floor: 0,
+            queued: HashMap::new(),
+        }
+    }
+}
+
+impl<K> BucketQueue<K>
+where
+    K: Clone + Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn priority(&self, key: &K) -> Option<u64> {
+        self.queued.get(key).copied()
+    }
+}
+
+impl<K> MinQueue<K> for BucketQueue<K>
+where
+    K: Clone + Eq + Hash,
+{
+    fn push(&mut self, key: K, priority: u64) -> bool {
+        if self.queued.get(&key).is_some_and(|&p| p <= priority) {
+            return false;
+        }
+        assert!(
+            priority >= self.floor,
+            "priority {priority} is below the last popped {}",
+            self.floor
+        );
+        if self.buckets.is_empty() {
+            self.base = priority;
+        }
+        while priority < self.base {
+            self.buckets.push_front(Vec::new());
+            self.base -= 1;
+        }
+        let slot = (priority - self.base) as usize;
+        if slot >= self.buckets.len() {
+            self.buckets.resize_with(slot + 1, Vec::new);
+        }
+        self.buckets[slot].push(key.clone());
+        self.queued.insert(key, priority);
+        true
+    }
+
+    fn pop(&mut self) -> Option<(K, u64)> {
+        while let Some(bucket) = self.buckets.front_mut() {
+            while let Some(key) = bucket.pop() {
+                // a stale copy if the key was moved to a lower bucket, or popped
+                if self.queued.get(&key) == Some(&self.base) {
+                    self.queued.remove(&key);
+                    self.floor = self.base;
+                    return Some((key, self.base));
+                }
+            }
+            self.buckets.pop_front();
+            self.base += 1;
+        }
+        None
+    }
+
+    fn len(&self) -> usize {
+        self.queued.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn drain(queue: &mut impl MinQueue<char>) -> Vec<(char, u64)> {
+        std::iter::from_fn(|| queue.pop()).collect()
+    }
+
+    fn fill(queue: &mut impl MinQueue<char>) {
+        assert!(queue.push('a', 7));
+        assert!(queue.push('b', 3));
+        assert!(queue.push('c', 5));
+        assert!(queue.push('d', 9));
+        // decrease-key, and an ignored increase
+        assert!(queue.push('d', 4));
+        assert!(!queue.push('b', 6));
+        assert_eq!(queue.len(), 4);
+    }
+
+    #[test]
+    fn test_queues_agree() {
+        let expected = vec![('b', 3), ('d', 4), ('c', 5), ('a', 7)];
+
+        let mut heap = IndexedHeap::new();
+        fill(&mut heap);
+        assert_eq!(heap.priority(&'d'), Some(4));
+        assert_eq!(heap.peek(), Some((&'b', 3)));
+        assert_eq!(drain(&mut heap), expected);
+
+        let mut buckets = BucketQueue::new();
+        fill(&mut buckets);
+        assert_eq!(buckets.priority(&'d'), Some(4));
+        assert_eq!(drain(&mut buckets), expected);
+        assert!(buckets.is_empty());
+    }
+
+    #[test]
+    fn test_heap_order() {
+        let mut heap = IndexedHeap::new();
+        let values = [50, 12, 88, 3, 41, 3, 97, 20, 64, 7];
+        for (i, &v) in values.iter().enumerate() {
+            heap.push(i, v);
+        }
+        heap.push(2, 1);
+        let popped: Vec<i32> = std::iter::from_fn(|| heap.pop()).map(|(_, p)| p).collect();
+        assert_eq!(popped, vec![1, 3, 3, 7, 12, 20, 41, 50, 64, 97]);
+    }
+
+    #[test]
+    #[should_panic(expected = "below the last popped")]
+    fn test_bucket_queue_is_monotone() {
+        let mut queue = BucketQueue::new();
+        queue.push('a', 10);
+        queue.pop();
+        queue.push('b', 2);
+    }
+}
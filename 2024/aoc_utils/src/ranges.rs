@@ -0,0 +1,349 @@
+use std::fmt::{self, Display, Formatter};
+use std::ops::{Add, Range, Sub};
+
+/// A set of values stored as sorted, disjoint, non-adjacent half-open ranges.
+///
+/// Every operation keeps the spans normalized, so two sets holding the same
+/// values always compare equal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RangeSet<T> {
+    spans: Vec<Range<T>>,
+}
+
+impl<T> Default for RangeSet<T> {
+    fn default() -> Self {
+        Self { spans: Vec::new() }
+    }
+}
+
+impl<T> RangeSet<T>
+where
+    T: Copy + Ord + Default + Add<Output = T> + Sub<Output = T>,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The normalized spans, sorted by start.
+    pub fn spans(&self) -> &[Range<T>] {
+        &self.spans
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Range<T>> {
+        self.spans.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Total number of values covered by the set.
+    pub fn len(&self) -> T {
+        self.spans
+            .iter()
+            .fold(T::default(), |acc, r| acc + (r.end - r.start))
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        // index of the first span starting after value
+        let i = self.spans.partition_point(|r| r.start <= value);
+        i > 0 && value < self.spans[i - 1].end
+    }
+
+    /// Adds `range`, merging it with every span it overlaps or touches.
+    pub fn insert(&mut self, range: Range<T>) {
+        if range.start >= range.end {
+            return;
+        }
+        // 1) first span that could touch the new range
+        let lo = self.spans.partition_point(|r| r.end < range.start);
+        // 2) first span that starts strictly after the new range
+        let hi = self.spans.partition_point(|r| r.start <= range.end);
+
+        let mut merged = range;
+        if lo < hi {
+            merged.start = merged.start.min(self.spans[lo].start);
+            merged.end = merged.end.max(self.spans[hi - 1].end);
+        }
+        self.spans.splice(lo..hi, std::iter::once(merged));
+    }
+
+    /// Removes every value of `range` from the set, splitting spans if needed.
+    pub fn remove(&mut self, range: Range<T>) {
+        if range.start >= range.end {
+            return;
+        }
+        let lo = self.spans.partition_point(|r| r.end <= range.start);
+        let hi = self.spans.partition_point(|r| r.start < range.end);
+        if lo >= hi {
+            return;
+        }
+
+        // Keep the parts of the first and last touched spans that stick out.
+        let mut kept = Vec::with_capacity(2);
+        let first = self.spans[lo].clone();
+        let last = self.spans[hi - 1].clone();
+        if first.start < range.start {
+            kept.push(first.start..range.start);
+        }
+        if range.end < last.end {
+            kept.push(range.end..last.end);
+        }
+        self.spans.splice(lo..hi, kept);
+    }
+
+    /// Union of both sets.
+    pub fn merge(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for r in &other.spans {
+            out.insert(r.clone());
+        }
+        out
+    }
+
+    /// Values present in both sets.
+    pub fn intersect(&self, other: &Self) -> Self {
+        let mut spans = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        while i < self.spans.len() && j < other.spans.len() {
+            let (a, b) = (&self.spans[i], &other.spans[j]);
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start < end {
+                spans.push(start..end);
+            }
+            // advance whichever span finishes first
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        Self { spans }
+    }
+
+    /// Values of `self` that are not in `other`.
+    pub fn subtract(&self, other: &Self) -> Self {
+        let mut out = self.clone();
+        for r in &other.spans {
+            out.remove(r.clone());
+        }
+        out
+    }
+
+    /// Values of `bounds` that are not in the set.
+    pub fn complement(&self, bounds: Range<T>) -> Self {
+        let mut out = Self::new();
+        out.insert(bounds);
+        out.subtract(self)
+    }
+
+    /// Leftmost span of exactly `len` values, taken from the first gap large enough.
+    pub fn first_fit(&self, len: T) -> Option<Range<T>> {
+        self.spans
+            .iter()
+            .find(|r| r.end - r.start >= len)
+            .map(|r| r.start..r.start + len)
+    }
+
+    /// Span of `len` values taken from the smallest gap that can hold it.
+    ///
+    /// Ties go to the leftmost gap.
+    pub fn best_fit(&self, len: T) -> Option<Range<T>> {
+        self.spans
+            .iter()
+            .filter(|r| r.end - r.start >= len)
+            .min_by_key(|r| r.end - r.start)
+            .map(|r| r.start..r.start + len)
+    }
+
+    /// Sends every value through `map`, splitting spans at piece boundaries.
+    pub fn map_through(&self, map: &OffsetMap<T>) -> Self {
+        let mut out = Self::new();
+        for span in &self.spans {
+            for piece in map.apply(span.clone()) {
+                out.insert(piece);
+            }
+        }
+        out
+    }
+}
+
+impl<T> FromIterator<Range<T>> for RangeSet<T>
+where
+    T: Copy + Ord + Default + Add<Output = T> + Sub<Output = T>,
+{
+    fn from_iter<I: IntoIterator<Item = Range<T>>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for r in iter {
+            set.insert(r);
+        }
+        set
+    }
+}
+
+impl<T: Display> Display for RangeSet<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (i, r) in self.spans.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}..{}", r.start, r.end)?;
+        }
+        write!(f, "}}")
+    }
+}
+
+/// Piecewise function moving each source range onto a destination start.
+///
+/// Values outside every piece map to themselves, like the almanac maps of
+/// "seed to soil" puzzles.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OffsetMap<T> {
+    /// (source range, destination start), sorted by source start.
+    pieces: Vec<(Range<T>, T)>,
+}
+
+impl<T> Default for OffsetMap<T> {
+    fn default() -> Self {
+        Self { pieces: Vec::new() }
+    }
+}
+
+impl<T> OffsetMap<T>
+where
+    T: Copy + Ord + Default + Add<Output = T> + Sub<Output = T>,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a piece sending `source` to `dest..dest + source.len()`.
+    ///
+    /// Pieces are expected not to overlap.
+    pub fn add(&mut self, source: Range<T>, dest: T) {
+        let at = self.pieces.partition_point(|(s, _)| s.start < source.start);
+        self.pieces.insert(at, (source, dest));
+    }
+
+    /// Image of a single value.
+    pub fn map_value(&self, value: T) -> T {
+        self.pieces
+            .iter()
+            .find(|(s, _)| s.contains(&value))
+            .map_or(value, |(s, d)| *d + (value - s.start))
+    }
+
+    /// Images of a range, one output range per piece (or gap) it crosses.
+    pub fn apply(&self, range: Range<T>) -> Vec<Range<T>> {
+        let mut out = Vec::new();
+        let mut cursor = range.start;
+        for (source, dest) in &self.pieces {
+            if cursor >= range.end {
+                break;
+            }
+            if source.end <= cursor {
+                continue;
+            }
+            // unmapped gap before this piece keeps its values
+            if cursor < source.start {
+                let gap_end = source.start.min(range.end);
+                out.push(cursor..gap_end);
+                cursor = gap_end;
+            }
+            let end = source.end.min(range.end);
+            if cursor < end {
+                let shift = cursor - source.start;
+                out.push(*dest + shift..*dest + shift + (end - cursor));
+                cursor = end;
+            }
+        }
+        if cursor < range.end {
+            out.push(cursor..range.end);
+        }
+        out
+    }
+}
+
+impl<T> FromIterator<(Range<T>, T)> for OffsetMap<T>
+where
+    T: Copy + Ord + Default + Add<Output = T> + Sub<Output = T>,
+{
+    fn from_iter<I: IntoIterator<Item = (Range<T>, T)>>(iter: I) -> Self {
+        let mut map = Self::new();
+        for (source, dest) in iter {
+            map.add(source, dest);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_merges() {
+        let mut set = RangeSet::new();
+        set.insert(5..8);
+        set.insert(1..3);
+        set.insert(3..4);
+        set.insert(7..12);
+        assert_eq!(set.spans(), &[1..4, 5..12]);
+        assert_eq!(set.len(), 10);
+        assert!(set.contains(11));
+        assert!(!set.contains(4));
+        set.insert(0..20);
+        assert_eq!(set.spans(), &[0..20]);
+    }
+
+    #[test]
+    fn test_remove_splits() {
+        let mut set: RangeSet<u32> = [0..10, 20..30].into_iter().collect();
+        set.remove(5..25);
+        assert_eq!(set.spans(), &[0..5, 25..30]);
+        set.remove(0..1);
+        set.remove(29..40);
+        assert_eq!(set.spans(), &[1..5, 25..29]);
+    }
+
+    #[test]
+    fn test_set_operations() {
+        let a: RangeSet<i64> = [0..10, 20..30].into_iter().collect();
+        let b: RangeSet<i64> = [5..25].into_iter().collect();
+        assert_eq!(a.merge(&b).spans(), &[0..30]);
+        assert_eq!(a.intersect(&b).spans(), &[5..10, 20..25]);
+        assert_eq!(a.subtract(&b).spans(), &[0..5, 25..30]);
+        assert_eq!(a.complement(-5..35).spans(), &[-5..0, 10..20, 30..35]);
+    }
+
+    #[test]
+    fn test_fits() {
+        // free spans of the day 9 example: 00...111...2...333.44.5555.6666.777.888899
+        let free: RangeSet<usize> = [2..5, 8..11, 12..15, 18..19, 21..22, 26..27, 31..32, 35..36]
+            .into_iter()
+            .collect();
+        assert_eq!(free.first_fit(2), Some(2..4));
+        assert_eq!(free.first_fit(1), Some(2..3));
+        assert_eq!(free.best_fit(1), Some(18..19));
+        assert_eq!(free.first_fit(4), None);
+    }
+
+    #[test]
+    fn test_map_through() {
+        // seed-to-soil map from the 2023 day 5 example
+        let map: OffsetMap<u64> = [(98..100, 50), (50..98, 52)].into_iter().collect();
+        assert_eq!(map.map_value(79), 81);
+        assert_eq!(map.map_value(14), 14);
+        assert_eq!(map.map_value(99), 51);
+
+        let seeds: RangeSet<u64> = [79..93, 55..68].into_iter().collect();
+        assert_eq!(seeds.map_through(&map).spans(), &[57..70, 81..95]);
+
+        let across: RangeSet<u64> = [40..100].into_iter().collect();
+        assert_eq!(across.map_through(&map).spans(), &[40..100]);
+        assert_eq!(map.apply(40..100), vec![40..50, 52..100, 50..52]);
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt::Display;
+
+use crate::display::Grid;
+use crate::geometry::cell_corners;
+use crate::grid::{Grid2D, ORTHOGONAL, SURROUNDING};
+use crate::parsing::Pos;
+
+/// Which cells count as touching when growing a region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Up, right, down, left (von Neumann).
+    Four,
+    /// The 4 orthogonal cells and the 4 diagonals (Moore).
+    Eight,
+}
+
+impl Connectivity {
+    pub(crate) fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Connectivity::Four => &ORTHOGONAL,
+            Connectivity::Eight => &SURROUNDING,
+        }
+    }
+}
+
+/// One connected group of equal cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region<T> {
+    pub label: usize,
+    pub value: T,
+    pub cells: Vec<Pos<usize>>,
+    /// Cell edges facing another region or the outside.
+    pub perimeter: usize,
+    /// Straight fence sides, counted as corners.
+    pub sides: usize,
+}
+
+impl<T> Region<T> {
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+/// Every region of a grid, and the grid of their labels.
+#[derive(Debug, Clone)]
+pub struct Regions<T> {
+    pub labels: Grid2D<usize>,
+    pub regions: Vec<Region<T>>,
+}
+
+/// Labels connected components of equal values, in reading order of their
+/// first cell.
+pub fn label_regions<T>(grid: &Grid2D<T>, connectivity: Connectivity) -> Regions<T>
+where
+    T: Clone + PartialEq,
+{
+    const UNSEEN: usize = usize::MAX;
+    let mut labels = Grid2D::new(grid.width(), grid.height(), UNSEEN);
+    let mut regions = Vec::new();
+
+    for start in grid.positions() {
+        if labels[start] != UNSEEN {
+            continue;
+        }
+        // flood fill from the first unlabeled cell
+        let label = regions.len();
+        let value = grid[start].clone();
+        let mut cells = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        labels[start] = label;
+        while let Some(pos) = queue.pop_front() {
+            cells.push(pos);
+            for &d in connectivity.offsets() {
+                if let Some(next) = grid.step(pos, d) {
+                    if labels[next] == UNSEEN && grid[next] == value {
+                        labels[next] = label;
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        regions.push(Region {
+            label,
+            value,
+            cells,
+            perimeter: 0,
+            sides: 0,
+        });
+    }
+
+    for region in &mut regions {
+        region.perimeter = perimeter(&labels, &region.cells);
+        region.sides = corners(&labels, &region.cells);
+    }
+
+    Regions { labels, regions }
+}
+
+fn same(labels: &Grid2D<usize>, pos: Pos<usize>, d: (isize, isize)) -> bool {
+    labels
+        .step(pos, d)
+        .is_some_and(|next| labels[next] == labels[pos])
+}
+
+fn perimeter(labels: &Grid2D<usize>, cells: &[Pos<usize>]) -> usize {
+    cells
+        .iter()
+        .map(|&p| ORTHOGONAL.iter().filter(|&&d| !same(labels, p, d)).count())
+        .sum()
+}
+
+fn corners(labels: &Grid2D<usize>, cells: &[Pos<usize>]) -> usize {
+    cells
+        .iter()
+        .map(|&p| cell_corners(|d| same(labels, p, d)))
+        .sum()
+}
+
+impl<T> Regions<T> {
+    pub fn len(&self) -> usize {
+        self.regions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.regions.is_empty()
+    }
+
+    pub fn region_at(&self, pos: Pos<usize>) -> Option<&Region<T>> {
+        self.labels.get(pos).map(|&label| &self.regions[label])
+    }
+
+    /// The label map, one letter per region cycling through `a..=z`.
+    pub fn render(&self) -> String {
+        self.labels
+            .render(|_, &label| (b'a' + (label % 26) as u8) as char)
+    }
+}
+
+impl<T: Display> Regions<T> {
+    /// Area, perimeter and sides of every region, one row each.
+    pub fn stats_table(&self) -> String {
+        let keys = vec!["value", "area", "perimeter", "sides"];
+        let columns: HashMap<&str, Vec<String>> = HashMap::from([
+            (
+                "value",
+                self.regions.iter().map(|r| r.value.to_string()).collect(),
+            ),
+            (
+                "area",
+                self.regions.iter().map(|r| r.area().to_string()).collect(),
+            ),
+            (
+                "perimeter",
+                self.regions
+                    .iter()
+                    .map(|r| r.perimeter.to_string())
+                    .collect(),
+            ),
+            (
+                "sides",
+                self.regions.iter().map(|r| r.sides.to_string()).collect(),
+            ),
+        ]);
+        let grid = Grid::new(
+            keys,
+            |k| columns.get(k).map(|v| v.as_slice()),
+            |k| k.to_string(),
+            |_, s: &String| s.clone(),
+        );
+        grid.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GARDEN: &str = "RRRRIICCFF
+RRRRIICCCF
+VVRRRCCFFF
+VVRCCCJFFF
+VVVVCJJCFE
+VVIVCCJJEE
+VVIIICJJEE
+MIIIIIJJEE
+MIIISIJEEE
+MMMISSJEEE";
+
+    #[test]
+    fn test_garden_prices() {
+        let grid = Grid2D::parse_with(GARDEN, |ch| ch);
+        let regions = label_regions(&grid, Connectivity::Four);
+        assert_eq!(regions.len(), 11);
+
+        let fence: usize = regions.regions.iter().map(|r| r.area() * r.perimeter).sum();
+        let bulk: usize = regions.regions.iter().map(|r| r.area() * r.sides).sum();
+        assert_eq!(fence, 1930);
+        assert_eq!(bulk, 1206);
+
+        let r = regions.region_at(Pos { line: 0, column: 0 }).unwrap();
+        assert_eq!((r.value, r.area(), r.perimeter, r.sides), ('R', 12, 18, 10));
+    }
+
+    #[test]
+    fn test_eight_connectivity() {
+        let grid = Grid2D::parse_with("X.X\n.X.\nX.X", |ch| ch);
+        assert_eq!(label_regions(&grid, Connectivity::Four).len(), 9);
+        let regions = label_regions(&grid, Connectivity::Eight);
+        assert_eq!(regions.len(), 2);
+        assert_eq!(regions.render(), "aba\nbab\naba");
+        // diagonal cells still have their own fences
+        assert_eq!(regions.regions[0].perimeter, 20);
+        assert!(regions.stats_table().contains("perimeter"));
+    }
+}
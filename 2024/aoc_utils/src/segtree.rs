@@ -0,0 +1,450 @@
+use std::marker::PhantomData;
+use std::ops::Range;
+
+/// How a [`SegmentTree`] merges values and applies range updates.
+///
+/// `combine` must be associative with `identity` as neutral element, and
+/// `apply` must distribute over it: updating a whole node gives the same
+/// value as updating its cells one by one.
+pub trait SegmentOp<T> {
+    type Update: Clone;
+
+    fn identity() -> T;
+    fn combine(left: &T, right: &T) -> T;
+    /// Value of a node covering `len` cells once `update` hits all of them.
+    fn apply(update: &Self::Update, value: &T, len: usize) -> T;
+    /// Single update doing `older` then `newer`.
+    fn compose(newer: &Self::Update, older: &Self::Update) -> Self::Update;
+}
+
+/// Range queries and lazy range updates in `O(log n)`.
+///
+/// A node's value already includes its own pending update; the update is only
+/// pushed to the children when a later range update splits the node.
+pub struct SegmentTree<T, Op: SegmentOp<T>> {
+    len: usize,
+    values: Vec<T>,
+    pending: Vec<Option<Op::Update>>,
+    op: PhantomData<Op>,
+}
+
+impl<T: Clone, Op: SegmentOp<T>> Clone for SegmentTree<T, Op> {
+    fn clone(&self) -> Self {
+        Self {
+            len: self.len,
+            values: self.values.clone(),
+            pending: self.pending.clone(),
+            op: PhantomData,
+        }
+    }
+}
+
+fn compose<T, Op: SegmentOp<T>>(
+    newer: Option<&Op::Update>,
+    older: Option<&Op::Update>,
+) -> Option<Op::Update> {
+    match (newer, older) {
+        (Some(n), Some(o)) => Some(Op::compose(n, o)),
+        (Some(u), None) | (None, Some(u)) => Some(u.clone()),
+        (None, None) => None,
+    }
+}
+
+impl<T: Clone, Op: SegmentOp<T>> SegmentTree<T, Op> {
+    /// `len` cells, all holding the identity.
+    pub fn new(len: usize) -> Self {
+        Self::from_slice(&vec![Op::identity(); len])
+    }
+
+    pub fn from_slice(cells: &[T]) -> Self {
+        let size = 4 * cells.len().max(1);
+        let mut tree = Self {
+            len: cells.len(),
+            values: vec![Op::identity(); size],
+            pending: vec![None; size],
+            op: PhantomData,
+        };
+        if !cells.is_empty() {
+            tree.build(1, 0, cells.len(), cells);
+        }
+        tree
+    }
+
+    fn build(&mut self, node: usize, lo: usize, hi: usize, cells: &[T]) {
+        if hi - lo == 1 {
+            self.values[node] = cells[lo].clone();
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        self.build(2 * node, lo, mid, cells);
+        self.build(2 * node + 1, mid, hi, cells);
+        self.values[node] = Op::combine(&self.values[2 * node], &self.values[2 * node + 1]);
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Value of `node` once the not yet pushed updates of its ancestors apply.
+    fn node_value(&self, node: usize, len: usize, above: Option<&Op::Update>) -> T {
+        match above {
+            Some(update) => Op::apply(update, &self.values[node], len),
+            None => self.values[node].clone(),
+        }
+    }
+
+    /// Combined value of the cells in `range`.
+    pub fn query(&self, range: Range<usize>) -> T {
+        if range.start >= range.end.min(self.len) {
+            return Op::identity();
+        }
+        self.query_node(1, 0, self.len, &range, None)
+    }
+
+    fn query_node(
+        &self,
+        node: usize,
+        lo: usize,
+        hi: usize,
+        range: &Range<usize>,
+        above: Option<&Op::Update>,
+    ) -> T {
+        if range.end <= lo || hi <= range.start {
+            return Op::identity();
+        }
+        if range.start <= lo && hi <= range.end {
+            return self.node_value(node, hi - lo, above);
+        }
+        let above = compose::<T, Op>(above, self.pending[node].as_ref());
+        let mid = (lo + hi) / 2;
+        Op::combine(
+            &self.query_node(2 * node, lo, mid, range, above.as_ref()),
+            &self.query_node(2 * node + 1, mid, hi, range, above.as_ref()),
+        )
+    }
+
+    pub fn get(&self, i: usize) -> T {
+        self.query(i..i + 1)
+    }
+
+    /// Applies `update` to every cell of `range`.
+    pub fn update(&mut self, range: Range<usize>, update: &Op::Update) {
+        if range.start >= range.end.min(self.len) {
+            return;
+        }
+        self.update_node(1, 0, self.len, &range, update);
+    }
+
+    fn tag(&mut self, node: usize, len: usize, update: &Op::Update) {
+        self.values[node] = Op::apply(update, &self.values[node], len);
+        self.pending[node] = compose::<T, Op>(Some(update), self.pending[node].as_ref());
+    }
+
+    fn push(&mut self, node: usize, lo: usize, mid: usize, hi: usize) {
+        if let Some(update) = self.pending[node].take() {
+            self.tag(2 * node, mid - lo, &update);
+            self.tag(2 * node + 1, hi - mid, &update);
+        }
+    }
+
+    fn update_node(
+        &mut self,
+        node: usize,
+        lo: usize,
+        hi: usize,
+        range: &Range<usize>,
+        update: &Op::Update,
+    ) {
+        if range.end <= lo || hi <= range.start {
+            return;
+        }
+        if range.start <= lo && hi <= range.end {
+            self.tag(node, hi - lo, update);
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        self.push(node, lo, mid, hi);
+        self.update_node(2 * node, lo, mid, range, update);
+        self.update_node(2 * node + 1, mid, hi, range, update);
+        self.values[node] = Op::combine(&self.values[2 * node], &self.values[2 * node + 1]);
+    }
+
+    /// Replaces cell `i`.
+    pub fn set(&mut self, i: usize, value: T) {
+        assert!(i < self.len, "{i} is outside the tree");
+        let (mut node, mut lo, mut hi) = (1, 0, self.len);
+        let mut path = Vec::new();
+        while hi - lo > 1 {
+            let mid = (lo + hi) / 2;
+            self.push(node, lo, mid, hi);
+            path.push(node);
+            (node, lo, hi) = if i < mid {
+                (2 * node, lo, mid)
+            } else {
+                (2 * node + 1, mid, hi)
+            };
+        }
+        self.values[node] = value;
+        self.pending[node] = None;
+        for node in path.into_iter().rev() {
+            self.values[node] = Op::combine(&self.values[2 * node], &self.values[2 * node + 1]);
+        }
+    }
+}
+
+/// Sums with "add to every cell" updates.
+#[derive(Debug, Clone, Copy)]
+pub struct SumAdd;
+
+impl SegmentOp<i64> for SumAdd {
+    type Update = i64;
+
+    fn identity() -> i64 {
+        0
+    }
+
+    fn combine(left: &i64, right: &i64) -> i64 {
+        left + right
+    }
+
+    fn apply(update: &i64, value: &i64, len: usize) -> i64 {
+        value + update * len as i64
+    }
+
+    fn compose(newer: &i64, older: &i64) -> i64 {
+        newer + older
+    }
+}
+
+/// Minimums with "add to every cell" updates.
+#[derive(Debug, Clone, Copy)]
+pub struct MinAdd;
+
+impl SegmentOp<i64> for MinAdd {
+    type Update = i64;
+
+    fn identity() -> i64 {
+        i64::MAX
+    }
+
+    fn combine(left: &i64, right: &i64) -> i64 {
+        *left.min(right)
+    }
+
+    fn apply(update: &i64, value: &i64, _len: usize) -> i64 {
+        value.saturating_add(*update)
+    }
+
+    fn compose(newer: &i64, older: &i64) -> i64 {
+        newer + older
+    }
+}
+
+/// Maximums with "add to every cell" updates.
+#[derive(Debug, Clone, Copy)]
+pub struct MaxAdd;
+
+impl SegmentOp<i64> for MaxAdd {
+    type Update = i64;
+
+    fn identity() -> i64 {
+        i64::MIN
+    }
+
+    fn combine(left: &i64, right: &i64) -> i64 {
+        *left.max(right)
+    }
+
+    fn apply(update: &i64, value: &i64, _len: usize) -> i64 {
+        value.saturating_add(*update)
+    }
+
+    fn compose(newer: &i64, older: &i64) -> i64 {
+        newer + older
+    }
+}
+
+/// Free cells of a span: the runs touching both ends and the longest one.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Run {
+    pub len: usize,
+    pub prefix: usize,
+    pub suffix: usize,
+    pub best: usize,
+}
+
+impl Run {
+    /// A single cell.
+    pub fn cell(free: bool) -> Self {
+        Self::filled(1, free)
+    }
+
+    fn filled(len: usize, free: bool) -> Self {
+        let run = if free { len } else { 0 };
+        Self {
+            len,
+            prefix: run,
+            suffix: run,
+            best: run,
+        }
+    }
+}
+
+/// Free runs with "mark every cell free (`true`) or used (`false`)" updates,
+/// for disk and memory allocation puzzles.
+#[derive(Debug, Clone, Copy)]
+pub struct FreeRuns;
+
+impl SegmentOp<Run> for FreeRuns {
+    type Update = bool;
+
+    fn identity() -> Run {
+        Run::default()
+    }
+
+    fn combine(left: &Run, right: &Run) -> Run {
+        Run {
+            len: left.len + right.len,
+            prefix: if left.prefix == left.len {
+                left.len + right.prefix
+            } else {
+                left.prefix
+            },
+            suffix: if right.suffix == right.len {
+                right.len + left.suffix
+            } else {
+                right.suffix
+            },
+            best: left.best.max(right.best).max(left.suffix + right.prefix),
+        }
+    }
+
+    fn apply(free: &bool, _value: &Run, len: usize) -> Run {
+        Run::filled(len, *free)
+    }
+
+    fn compose(newer: &bool, _older: &bool) -> bool {
+        *newer
+    }
+}
+
+impl SegmentTree<Run, FreeRuns> {
+    /// Tree over `cells`, `true` for the free ones.
+    pub fn from_free(cells: impl IntoIterator<Item = bool>) -> Self {
+        let cells: Vec<Run> = cells.into_iter().map(Run::cell).collect();
+        Self::from_slice(&cells)
+    }
+
+    /// Start of the leftmost run of at least `k` free cells.
+    pub fn leftmost_free_run(&self, k: usize) -> Option<usize> {
+        if k == 0 {
+            return Some(0);
+        }
+        if self.is_empty() || self.values[1].best < k {
+            return None;
+        }
+        let (mut node, mut lo, mut hi) = (1, 0, self.len);
+        let mut above: Option<bool> = None;
+        while hi - lo > 1 {
+            let mid = (lo + hi) / 2;
+            above = compose::<Run, FreeRuns>(above.as_ref(), self.pending[node].as_ref());
+            let left = self.node_value(2 * node, mid - lo, above.as_ref());
+            let right = self.node_value(2 * node + 1, hi - mid, above.as_ref());
+            if left.best >= k {
+                (node, hi) = (2 * node, mid);
+            } else if left.suffix + right.prefix >= k {
+                return Some(mid - left.suffix);
+            } else {
+                (node, lo) = (2 * node + 1, mid);
+            }
+        }
+        Some(lo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lazy_updates() {
+        let cells = [5, -2, 7, 0, 3, 8, -4];
+        let mut sums: SegmentTree<i64, SumAdd> = SegmentTree::from_slice(&cells);
+        let mut mins: SegmentTree<i64, MinAdd> = SegmentTree::from_slice(&cells);
+        let mut maxs: SegmentTree<i64, MaxAdd> = SegmentTree::from_slice(&cells);
+        let mut naive = cells.to_vec();
+
+        for (range, delta) in [(1..5, 3), (0..7, -1), (4..6, 10), (2..3, -20)] {
+            sums.update(range.clone(), &delta);
+            mins.update(range.clone(), &delta);
+            maxs.update(range.clone(), &delta);
+            naive[range].iter_mut().for_each(|v| *v += delta);
+        }
+        sums.set(3, 100);
+        mins.set(3, 100);
+        maxs.set(3, 100);
+        naive[3] = 100;
+
+        for start in 0..naive.len() {
+            for end in start + 1..=naive.len() {
+                let slice = &naive[start..end];
+                assert_eq!(sums.query(start..end), slice.iter().sum::<i64>());
+                assert_eq!(mins.query(start..end), *slice.iter().min().unwrap());
+                assert_eq!(maxs.query(start..end), *slice.iter().max().unwrap());
+            }
+        }
+        assert_eq!(sums.get(6), naive[6]);
+        assert_eq!(sums.query(3..3), 0);
+        assert_eq!(SegmentTree::<i64, SumAdd>::new(0).query(0..4), 0);
+    }
+
+    #[test]
+    fn test_free_runs() {
+        let mut disk = SegmentTree::from_free("..#...#....".chars().map(|c| c == '.'));
+        assert_eq!(disk.leftmost_free_run(1), Some(0));
+        assert_eq!(disk.leftmost_free_run(3), Some(3));
+        assert_eq!(disk.leftmost_free_run(4), Some(7));
+        assert_eq!(disk.leftmost_free_run(5), None);
+
+        disk.update(3..5, &false);
+        assert_eq!(disk.leftmost_free_run(2), Some(0));
+        disk.update(0..2, &false);
+        assert_eq!(disk.leftmost_free_run(2), Some(7));
+        // freeing across the old walls merges the runs
+        disk.update(5..7, &true);
+        assert_eq!(disk.leftmost_free_run(6), Some(5));
+        assert_eq!(disk.query(0..11).best, 6);
+    }
+
+    #[test]
+    fn test_day_9_compaction() {
+        // files as (id, start, len) and the free map of the whole disk
+        let mut files = Vec::new();
+        let mut free = Vec::new();
+        for (i, ch) in "2333133121414131402".chars().enumerate() {
+            let len = ch.to_digit(10).unwrap() as usize;
+            if i % 2 == 0 {
+                files.push((i / 2, free.len(), len));
+            }
+            free.extend(std::iter::repeat_n(i % 2 == 1, len));
+        }
+
+        let mut disk = SegmentTree::from_free(free);
+        for (_, start, len) in files.iter_mut().rev() {
+            if let Some(target) = disk.leftmost_free_run(*len).filter(|t| t < start) {
+                disk.update(target..target + *len, &false);
+                disk.update(*start..*start + *len, &true);
+                *start = target;
+            }
+        }
+        let check_sum: usize = files
+            .iter()
+            .flat_map(|&(id, start, len)| (start..start + len).map(move |p| id * p))
+            .sum();
+        assert_eq!(check_sum, 2858);
+    }
+}
@@ -0,0 +1,370 @@
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+
+use crate::bitset::BitSet;
+
+/// Up to this many nodes the clique search keeps one neighbor bitset per
+/// node for the whole graph (2 MiB at the limit). Bigger graphs are searched
+/// one neighborhood at a time, in degeneracy order.
+const DENSE_LIMIT: usize = 4096;
+
+/// Undirected simple graph, nodes numbered in order of first appearance.
+///
+/// Unlike day 5's directed `topo::Graph<T>`, an edge `a-b` is also `b-a`.
+#[derive(Debug, Clone)]
+pub struct UndirectedGraph<T> {
+    ids: HashMap<T, usize>,
+    nodes: Vec<T>,
+    neighbors: Vec<BTreeSet<usize>>,
+}
+
+impl<T> Default for UndirectedGraph<T> {
+    fn default() -> Self {
+        Self {
+            ids: HashMap::new(),
+            nodes: Vec::new(),
+            neighbors: Vec::new(),
+        }
+    }
+}
+
+impl<T> UndirectedGraph<T>
+where
+    T: Clone + Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Graph of every `(a, b)` edge, e.g. the `kh-tc` lines of a network map.
+    pub fn from_edges(edges: impl IntoIterator<Item = (T, T)>) -> Self {
+        let mut graph = Self::new();
+        for (a, b) in edges {
+            graph.add_edge(a, b);
+        }
+        graph
+    }
+
+    fn id(&mut self, node: T) -> usize {
+        if let Some(&id) = self.ids.get(&node) {
+            return id;
+        }
+        let id = self.nodes.len();
+        self.ids.insert(node.clone(), id);
+        self.nodes.push(node);
+        self.neighbors.push(BTreeSet::new());
+        id
+    }
+
+    /// Adds both nodes if needed; self loops only add the node.
+    pub fn add_edge(&mut self, a: T, b: T) {
+        let (a, b) = (self.id(a), self.id(b));
+        if a != b {
+            self.neighbors[a].insert(b);
+            self.neighbors[b].insert(a);
+        }
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.neighbors.iter().map(|n| n.len()).sum::<usize>() / 2
+    }
+
+    pub fn nodes(&self) -> &[T] {
+        &self.nodes
+    }
+
+    pub fn contains_edge(&self, a: &T, b: &T) -> bool {
+        match (self.ids.get(a), self.ids.get(b)) {
+            (Some(&a), Some(&b)) => self.neighbors[a].contains(&b),
+            _ => false,
+        }
+    }
+
+    pub fn neighbors(&self, node: &T) -> impl Iterator<Item = &T> + '_ {
+        self.ids
+            .get(node)
+            .into_iter()
+            .flat_map(|&id| self.neighbors[id].iter().map(|&n| &self.nodes[n]))
+    }
+
+    pub fn degree(&self, node: &T) -> usize {
+        self.ids.get(node).map_or(0, |&id| self.neighbors[id].len())
+    }
+
+    fn to_nodes(&self, ids: &[usize]) -> Vec<T> {
+        ids.iter().map(|&id| self.nodes[id].clone()).collect()
+    }
+
+    /// Every set of 3 mutually connected nodes, each listed once.
+    pub fn triangles(&self) -> Vec<[T; 3]> {
+        let mut triangles = Vec::new();
+        for (a, na) in self.neighbors.iter().enumerate() {
+            for &b in na.range(a + 1..) {
+                for &c in self.neighbors[b].range(b + 1..) {
+                    if na.contains(&c) {
+                        triangles.push([a, b, c].map(|id| self.nodes[id].clone()));
+                    }
+                }
+            }
+        }
+        triangles
+    }
+
+    /// Every clique that can't be grown any further, by Bron–Kerbosch with
+    /// pivoting.
+    pub fn maximal_cliques(&self) -> Vec<Vec<T>> {
+        let mut cliques = Vec::new();
+        self.search(self.node_count() <= DENSE_LIMIT, &mut |clique| {
+            cliques.push(self.to_nodes(clique));
+            0
+        });
+        cliques
+    }
+
+    /// A largest clique, the one found first if there are several.
+    pub fn maximum_clique(&self) -> Vec<T> {
+        let mut best = Vec::new();
+        self.search(self.node_count() <= DENSE_LIMIT, &mut |clique| {
+            best = clique.to_vec();
+            clique.len() + 1
+        });
+        self.to_nodes(&best)
+    }
+
+    /// Calls `report` on maximal cliques, as node ids in increasing order.
+    ///
+    /// `report` returns the smallest clique size still wanted, so branches
+    /// that can't reach it are cut.
+    fn search(&self, dense: bool, report: &mut dyn FnMut(&[usize]) -> usize) {
+        let n = self.node_count();
+        if n == 0 {
+            return;
+        }
+        if dense {
+            let adjacency: Vec<BitSet> = self
+                .neighbors
+                .iter()
+                .map(|ns| {
+                    let mut set = BitSet::new(n);
+                    ns.iter().for_each(|&id| {
+                        set.insert(id);
+                    });
+                    set
+                })
+                .collect();
+            let mut floor = 0;
+            expand(
+                &adjacency,
+                &mut Vec::new(),
+                BitSet::full(n),
+                BitSet::new(n),
+                &mut floor,
+                &mut |clique| {
+                    let mut clique = clique.to_vec();
+                    clique.sort_unstable();
+                    report(&clique)
+                },
+            );
+            return;
+        }
+
+        // one small search per node: its later neighbors are the candidates,
+        // the earlier ones were already covered
+        let order = self.degeneracy_order();
+        let mut rank = vec![0; n];
+        for (i, &v) in order.iter().enumerate() {
+            rank[v] = i;
+        }
+        let mut floor = 0;
+        for &v in &order {
+            let local: Vec<usize> = self.neighbors[v].iter().copied().collect();
+            let mut candidates = BitSet::new(local.len());
+            let mut excluded = BitSet::new(local.len());
+            for (i, u) in local.iter().enumerate() {
+                if rank[*u] > rank[v] {
+                    candidates.insert(i);
+                } else {
+                    excluded.insert(i);
+                }
+            }
+            if 1 + candidates.len() < floor {
+                continue;
+            }
+            let adjacency: Vec<BitSet> = local
+                .iter()
+                .map(|u| {
+                    let mut set = BitSet::new(local.len());
+                    for (j, w) in local.iter().enumerate() {
+                        if self.neighbors[*u].contains(w) {
+                            set.insert(j);
+                        }
+                    }
+                    set
+                })
+                .collect();
+
+            let mut local_floor = floor.saturating_sub(1);
+            expand(
+                &adjacency,
+                &mut Vec::new(),
+                candidates,
+                excluded,
+                &mut local_floor,
+                &mut |clique| {
+                    let mut clique: Vec<usize> = clique.iter().map(|&i| local[i]).collect();
+                    clique.push(v);
+                    clique.sort_unstable();
+                    floor = report(&clique);
+                    floor.saturating_sub(1)
+                },
+            );
+        }
+    }
+
+    /// Nodes by repeatedly taking one of least remaining degree.
+    fn degeneracy_order(&self) -> Vec<usize> {
+        let n = self.node_count();
+        let mut degree: Vec<usize> = self.neighbors.iter().map(|ns| ns.len()).collect();
+        let mut queue: BTreeSet<(usize, usize)> =
+            degree.iter().enumerate().map(|(v, &d)| (d, v)).collect();
+        let mut removed = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        while let Some((_, v)) = queue.pop_first() {
+            removed[v] = true;
+            order.push(v);
+            for &u in &self.neighbors[v] {
+                if !removed[u] {
+                    queue.remove(&(degree[u], u));
+                    degree[u] -= 1;
+                    queue.insert((degree[u], u));
+                }
+            }
+        }
+        order
+    }
+}
+
+/// Bron–Kerbosch step: `clique` grows with nodes of `candidates`, `excluded`
+/// holds nodes whose cliques were all reported already.
+fn expand(
+    adjacency: &[BitSet],
+    clique: &mut Vec<usize>,
+    mut candidates: BitSet,
+    mut excluded: BitSet,
+    floor: &mut usize,
+    report: &mut dyn FnMut(&[usize]) -> usize,
+) {
+    if candidates.is_empty() {
+        if excluded.is_empty() && clique.len() >= *floor {
+            *floor = report(clique);
+        }
+        return;
+    }
+
+    // branching on the pivot's neighbors would only find the same cliques
+    let pivot = candidates
+        .iter()
+        .chain(excluded.iter())
+        .max_by_key(|&u| candidates.intersection_len(&adjacency[u]))
+        .expect("candidates aren't empty");
+    for v in candidates.difference(&adjacency[pivot]).iter() {
+        if clique.len() + candidates.len() < *floor {
+            break;
+        }
+        clique.push(v);
+        expand(
+            adjacency,
+            clique,
+            candidates.intersection(&adjacency[v]),
+            excluded.intersection(&adjacency[v]),
+            floor,
+            report,
+        );
+        clique.pop();
+        candidates.remove(v);
+        excluded.insert(v);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NETWORK: &str = "kh-tc qp-kh de-cg ka-co yn-aq qp-ub cg-tb vc-aq tb-ka wh-tc yn-cg
+        kh-ub ta-co de-co tc-td tb-wq wh-td ta-ka td-qp aq-cg wq-ub ub-vc de-ta wq-aq wq-vc
+        wh-yn ka-de kh-ta co-tc wh-qp tb-vc td-yn";
+
+    fn network() -> UndirectedGraph<&'static str> {
+        UndirectedGraph::from_edges(
+            NETWORK
+                .split_whitespace()
+                .map(|edge| edge.split_once('-').unwrap()),
+        )
+    }
+
+    fn sorted(mut clique: Vec<&str>) -> String {
+        clique.sort_unstable();
+        clique.join(",")
+    }
+
+    #[test]
+    fn test_triangles() {
+        let graph = network();
+        assert_eq!((graph.node_count(), graph.edge_count()), (16, 32));
+        assert!(graph.contains_edge(&"tc", &"kh") && !graph.contains_edge(&"tc", &"ka"));
+        assert_eq!(graph.degree(&"de"), 4);
+
+        let triangles = graph.triangles();
+        assert_eq!(triangles.len(), 12);
+        let with_t = triangles
+            .iter()
+            .filter(|t| t.iter().any(|n| n.starts_with('t')))
+            .count();
+        assert_eq!(with_t, 7);
+    }
+
+    #[test]
+    fn test_cliques_dense_and_sparse() {
+        let graph = network();
+        assert_eq!(sorted(graph.maximum_clique()), "co,de,ka,ta");
+
+        for dense in [true, false] {
+            let mut cliques = Vec::new();
+            graph.search(dense, &mut |c| {
+                cliques.push(sorted(graph.to_nodes(c)));
+                0
+            });
+            cliques.sort();
+            let expected: Vec<String> = graph
+                .maximal_cliques()
+                .into_iter()
+                .map(sorted)
+                .collect::<BTreeSet<_>>()
+                .into_iter()
+                .collect();
+            assert_eq!(cliques, expected, "dense: {dense}");
+            assert!(cliques.contains(&"co,de,ka,ta".to_string()));
+
+            let mut best = Vec::new();
+            graph.search(dense, &mut |c| {
+                best = c.to_vec();
+                c.len() + 1
+            });
+            assert_eq!(sorted(graph.to_nodes(&best)), "co,de,ka,ta");
+        }
+    }
+
+    #[test]
+    fn test_isolated_and_empty() {
+        let mut graph = UndirectedGraph::from_edges([(1, 2), (2, 3)]);
+        graph.add_edge(4, 4);
+        let mut cliques = graph.maximal_cliques();
+        cliques.sort();
+        assert_eq!(cliques, vec![vec![1, 2], vec![2, 3], vec![4]]);
+        assert!(UndirectedGraph::<u8>::new().maximum_clique().is_empty());
+    }
+}
@@ -13,10 +13,55 @@ fn part1() {
     .unwrap();
 }
 
+#[divan::bench]
+fn part1_nom() {
+    part1::process_with(
+        divan::black_box(include_str!("../input1.txt")),
+        part1::Variant::Nom,
+    )
+    .unwrap();
+}
+
+#[divan::bench]
+fn part1_nom_iterator() {
+    part1::process_with(
+        divan::black_box(include_str!("../input1.txt")),
+        part1::Variant::NomIterator,
+    )
+    .unwrap();
+}
+
 #[divan::bench]
 fn part2() {
     part2::process(divan::black_box(include_str!(
         "../input2.txt",
     )))
     .unwrap();
-}
\ No newline at end of file
+}
+
+#[divan::bench]
+fn part2_hashmap() {
+    part2::process_with(
+        divan::black_box(include_str!("../input2.txt")),
+        part2::Variant::HashMap,
+    )
+    .unwrap();
+}
+
+#[divan::bench]
+fn part2_hashmap_nom() {
+    part2::process_with(
+        divan::black_box(include_str!("../input2.txt")),
+        part2::Variant::HashMapNom,
+    )
+    .unwrap();
+}
+
+#[divan::bench]
+fn part2_nom() {
+    part2::process_with(
+        divan::black_box(include_str!("../input2.txt")),
+        part2::Variant::Nom,
+    )
+    .unwrap();
+}
@@ -1,17 +1,139 @@
+use miette::miette;
+use nom::{
+    character::complete::{line_ending, space1, u32},
+    combinator::{iterator, opt},
+    multi::separated_list1,
+    sequence::{separated_pair, terminated},
+    IResult, Parser,
+};
+
+#[tracing::instrument]
+pub fn process(input: &str) -> miette::Result<String> {
+    process_with(input, Variant::Split)
+}
+
+/// How the two location lists are read.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// `part1`: `split_whitespace` and `str::parse`.
+    Split,
+    /// `part1_nom`: a nom parser collecting every pair.
+    Nom,
+    /// `part1_nom_iterator`: nom pairs pulled one by one, without the pair list.
+    NomIterator,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 3] = [Variant::Split, Variant::Nom, Variant::NomIterator];
+}
+
 #[tracing::instrument]
-pub fn process(_input: &str) -> miette::Result<String> {
-    todo!("day 01 - part 1");
+pub fn process_with(input: &str, variant: Variant) -> miette::Result<String> {
+    let (left, right) = match variant {
+        Variant::Split => parse_lists(input)?,
+        Variant::Nom => parse_all_pairs(input)?.into_iter().unzip(),
+        Variant::NomIterator => {
+            let mut pairs = iterator(input, terminated(pair, opt(line_ending)));
+            let lists = pairs.by_ref().unzip();
+            // the iterator stops quietly on the first line that isn't a pair
+            let (rest, ()) = pairs
+                .finish()
+                .map_err(|e| miette!("Failed to parse input: {}", e))?;
+            ensure_consumed(rest)?;
+            lists
+        }
+    };
+    Ok(total_distance(left, right).to_string())
+}
+
+/// Left and right columns of the input.
+pub fn parse_lists(input: &str) -> miette::Result<(Vec<u32>, Vec<u32>)> {
+    let mut left = Vec::new();
+    let mut right = Vec::new();
+    for line in input.lines().filter(|line| !line.trim().is_empty()) {
+        let mut ids = line.split_whitespace().map(str::parse::<u32>);
+        match (ids.next(), ids.next(), ids.next()) {
+            (Some(Ok(l)), Some(Ok(r)), None) => {
+                left.push(l);
+                right.push(r);
+            }
+            _ => return Err(miette!("Not a pair of location ids: {line:?}")),
+        }
+    }
+    Ok((left, right))
+}
+
+pub fn pair(input: &str) -> IResult<&str, (u32, u32)> {
+    separated_pair(u32, space1, u32).parse(input)
+}
+
+pub fn parse_pairs(input: &str) -> IResult<&str, Vec<(u32, u32)>> {
+    separated_list1(line_ending, pair).parse(input)
+}
+
+/// [`parse_pairs`] over the whole input, failing on any line left over.
+pub fn parse_all_pairs(input: &str) -> miette::Result<Vec<(u32, u32)>> {
+    let (rest, pairs) = parse_pairs(input).map_err(|e| miette!("Failed to parse input: {}", e))?;
+    ensure_consumed(rest)?;
+    Ok(pairs)
+}
+
+fn ensure_consumed(rest: &str) -> miette::Result<()> {
+    match rest.trim().lines().next() {
+        None => Ok(()),
+        Some(line) => Err(miette!("Not a pair of location ids: {line:?}")),
+    }
+}
+
+/// Sum of the distances between the smallest ids of each list, then the
+/// second smallest, and so on.
+pub fn total_distance(mut left: Vec<u32>, mut right: Vec<u32>) -> u32 {
+    left.sort_unstable();
+    right.sort_unstable();
+    left.iter().zip(&right).map(|(l, r)| l.abs_diff(*r)).sum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const TEST1: &str = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3";
+
     #[test]
     fn test_process() -> miette::Result<()> {
-        todo!("haven't built test yet");
-        let input = "";
-        assert_eq!("", process(input)?);
+        assert_eq!("11", process(TEST1)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_variants_agree() -> miette::Result<()> {
+        let input = include_str!("../input1.txt");
+        let expected = process(input)?;
+        for variant in Variant::ALL {
+            assert_eq!("11", process_with(TEST1, variant)?, "{variant:?}");
+            assert_eq!(expected, process_with(input, variant)?, "{variant:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bad_line() -> miette::Result<()> {
+        assert!(parse_lists("3   4\n4   x").is_err());
+        assert!(parse_lists("3   4   5").is_err());
+        for input in ["3   4\n4   x", "3   4   5", "x   4"] {
+            for variant in Variant::ALL {
+                assert!(process_with(input, variant).is_err(), "{variant:?}");
+            }
+        }
+        // a trailing newline is fine
+        for variant in Variant::ALL {
+            assert_eq!("1", process_with("3   4\n", variant)?, "{variant:?}");
+        }
         Ok(())
     }
 }
@@ -1,17 +1,101 @@
+use std::collections::HashMap;
+
+use crate::part1::{parse_all_pairs, parse_lists};
+
 #[tracing::instrument]
-pub fn process(_input: &str) -> miette::Result<String> {
-    todo!("day 01 - part 2");
+pub fn process(input: &str) -> miette::Result<String> {
+    process_with(input, Variant::Scan)
+}
+
+/// How the similarity score is counted, and the lists read for it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// `part2`: counts every left id by scanning the whole right list.
+    Scan,
+    /// `part2_hashmap`: counts the right ids once in a `HashMap`.
+    HashMap,
+    /// `part2_hashmap_nom`: same as `HashMap`, parsed with nom.
+    HashMapNom,
+    /// `part2_nom`: same as `Scan`, parsed with nom.
+    Nom,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 4] = [
+        Variant::Scan,
+        Variant::HashMap,
+        Variant::HashMapNom,
+        Variant::Nom,
+    ];
+}
+
+#[tracing::instrument]
+pub fn process_with(input: &str, variant: Variant) -> miette::Result<String> {
+    let (left, right) = match variant {
+        Variant::Scan | Variant::HashMap => parse_lists(input)?,
+        Variant::HashMapNom | Variant::Nom => parse_all_pairs(input)?.into_iter().unzip(),
+    };
+    let score = match variant {
+        Variant::Scan | Variant::Nom => similarity_scan(&left, &right),
+        Variant::HashMap | Variant::HashMapNom => similarity_counts(&left, &right),
+    };
+    Ok(score.to_string())
+}
+
+/// Every left id times the number of times it appears on the right, in
+/// `O(left * right)`.
+pub fn similarity_scan(left: &[u32], right: &[u32]) -> u32 {
+    left.iter()
+        .map(|l| l * right.iter().filter(|r| *r == l).count() as u32)
+        .sum()
+}
+
+/// Same as [`similarity_scan`], counting the right ids up front.
+pub fn similarity_counts(left: &[u32], right: &[u32]) -> u32 {
+    let mut counts: HashMap<u32, u32> = HashMap::new();
+    for r in right {
+        *counts.entry(*r).or_default() += 1;
+    }
+    left.iter()
+        .map(|l| l * counts.get(l).copied().unwrap_or(0))
+        .sum()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const TEST1: &str = "3   4
+4   3
+2   5
+1   3
+3   9
+3   3";
+
     #[test]
     fn test_process() -> miette::Result<()> {
-        todo!("haven't built test yet");
-        let input = "";
-        assert_eq!("", process(input)?);
+        assert_eq!("31", process(TEST1)?);
         Ok(())
     }
+
+    #[test]
+    fn test_variants_agree() -> miette::Result<()> {
+        let input = include_str!("../input2.txt");
+        let expected = process(input)?;
+        for variant in Variant::ALL {
+            assert_eq!("31", process_with(TEST1, variant)?, "{variant:?}");
+            assert_eq!(expected, process_with(input, variant)?, "{variant:?}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_bad_line() {
+        for variant in Variant::ALL {
+            assert!(
+                process_with("3   4\n4   x", variant).is_err(),
+                "{variant:?}"
+            );
+        }
+    }
 }
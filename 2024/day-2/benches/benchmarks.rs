@@ -19,4 +19,13 @@ fn part2() {
         "../input2.txt",
     )))
     .unwrap();
-}
\ No newline at end of file
+}
+
+#[divan::bench]
+fn part2_quadratic() {
+    part2::process_with(
+        divan::black_box(include_str!("../input2.txt")),
+        part2::Variant::Quadratic,
+    )
+    .unwrap();
+}
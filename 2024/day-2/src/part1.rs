@@ -1,17 +1,72 @@
+use miette::miette;
+
 #[tracing::instrument]
-pub fn process(_input: &str) -> miette::Result<String> {
-    todo!("day 01 - part 1");
+pub fn process(input: &str) -> miette::Result<String> {
+    let reports = parse_reports(input)?;
+    let safe = reports.iter().filter(|levels| is_safe(levels)).count();
+    Ok(safe.to_string())
+}
+
+pub type Report = Vec<i32>;
+
+/// One report of levels per line.
+pub fn parse_reports(input: &str) -> miette::Result<Vec<Report>> {
+    input
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.split_whitespace()
+                .map(|level| {
+                    level
+                        .parse::<i32>()
+                        .map_err(|e| miette!("Bad level {level:?} in {line:?}: {e}"))
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// A safe step up: by at least 1 and at most 3.
+pub fn increasing(a: i32, b: i32) -> bool {
+    (1..=3).contains(&(b - a))
+}
+
+pub fn decreasing(a: i32, b: i32) -> bool {
+    increasing(b, a)
+}
+
+/// Levels all increasing or all decreasing, by safe steps.
+pub fn is_safe(levels: &[i32]) -> bool {
+    [increasing, decreasing]
+        .iter()
+        .any(|ok| levels.windows(2).all(|w| ok(w[0], w[1])))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const TEST1: &str = "7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9";
+
     #[test]
     fn test_process() -> miette::Result<()> {
-        todo!("haven't built test yet");
-        let input = "";
-        assert_eq!("", process(input)?);
+        assert_eq!("2", process(TEST1)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_safe() -> miette::Result<()> {
+        let safe: Vec<bool> = parse_reports(TEST1)?
+            .iter()
+            .map(|levels| is_safe(levels))
+            .collect();
+        assert_eq!(safe, vec![true, false, false, false, false, true]);
+        assert!(parse_reports("1 2 x").is_err());
         Ok(())
     }
 }
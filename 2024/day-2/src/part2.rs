@@ -1,17 +1,169 @@
+use crate::part1::{decreasing, increasing, is_safe, parse_reports};
+
 #[tracing::instrument]
-pub fn process(_input: &str) -> miette::Result<String> {
-    todo!("day 01 - part 2");
+pub fn process(input: &str) -> miette::Result<String> {
+    process_with(input, Variant::Linear)
+}
+
+/// How the problem dampener looks for a level to remove.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// `part2`: one pass over the levels, see [`dampen`].
+    Linear,
+    /// `part2_quadratic`: re-checks the report without each level in turn.
+    Quadratic,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 2] = [Variant::Linear, Variant::Quadratic];
+}
+
+#[tracing::instrument]
+pub fn process_with(input: &str, variant: Variant) -> miette::Result<String> {
+    let safe = verdicts(input, variant)?
+        .iter()
+        .filter(|v| **v != Verdict::Unsafe)
+        .count();
+    Ok(safe.to_string())
+}
+
+/// What the problem dampener made of a report.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    Safe,
+    /// Safe once the level at this index is removed, the first such index.
+    Dampened(usize),
+    Unsafe,
+}
+
+/// The verdict of every report, in input order.
+pub fn verdicts(input: &str, variant: Variant) -> miette::Result<Vec<Verdict>> {
+    let dampen = match variant {
+        Variant::Linear => dampen,
+        Variant::Quadratic => dampen_quadratic,
+    };
+    Ok(parse_reports(input)?
+        .iter()
+        .enumerate()
+        .map(|(i, levels)| {
+            let verdict = dampen(levels);
+            tracing::debug!(report = i, ?levels, ?verdict);
+            verdict
+        })
+        .collect())
+}
+
+/// Tries every removal, `O(n²)` per report.
+pub fn dampen_quadratic(levels: &[i32]) -> Verdict {
+    if is_safe(levels) {
+        return Verdict::Safe;
+    }
+    (0..levels.len())
+        .find(|&skip| {
+            let rest: Vec<i32> = levels
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != skip)
+                .map(|(_, &l)| l)
+                .collect();
+            is_safe(&rest)
+        })
+        .map_or(Verdict::Unsafe, Verdict::Dampened)
+}
+
+/// Same verdict as [`dampen_quadratic`], in one pass per direction.
+pub fn dampen(levels: &[i32]) -> Verdict {
+    let up = dampen_towards(levels, increasing);
+    let down = dampen_towards(levels, decreasing);
+    match (up, down) {
+        (Some(None), _) | (_, Some(None)) => Verdict::Safe,
+        (Some(Some(a)), Some(Some(b))) => Verdict::Dampened(a.min(b)),
+        (Some(Some(r)), None) | (None, Some(Some(r))) => Verdict::Dampened(r),
+        (None, None) => Verdict::Unsafe,
+    }
+}
+
+/// `Some(None)` if every step is `ok`, `Some(Some(r))` if they are once
+/// level `r` (the first possible one) is removed.
+///
+/// Walking the levels, the report is either still clean, or one level was
+/// dropped and the last kept level is `i - 1` or `i`. Two reports dampened
+/// with the same last level behave the same from there on, so at most two of
+/// them are tracked: the ones that dropped the earliest level.
+fn dampen_towards(levels: &[i32], ok: fn(i32, i32) -> bool) -> Option<Option<usize>> {
+    let mut clean = true;
+    // (last kept level, removed level)
+    let mut dampened: Vec<(usize, usize)> = Vec::with_capacity(4);
+    let mut next = Vec::with_capacity(4);
+    for i in 1..levels.len() {
+        next.clear();
+        next.extend(
+            dampened
+                .iter()
+                .filter(|&&(last, _)| ok(levels[last], levels[i]))
+                .map(|&(_, removed)| (i, removed)),
+        );
+        if clean {
+            // drop level i, or level i - 1
+            next.push((i - 1, i));
+            if i == 1 || ok(levels[i - 2], levels[i]) {
+                next.push((i, i - 1));
+            }
+            clean = ok(levels[i - 1], levels[i]);
+        }
+        next.sort_unstable();
+        next.dedup_by_key(|&mut (last, _)| last);
+        std::mem::swap(&mut dampened, &mut next);
+    }
+
+    if clean {
+        Some(None)
+    } else {
+        dampened.iter().map(|&(_, removed)| removed).min().map(Some)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const TEST1: &str = "7 6 4 2 1
+1 2 7 8 9
+9 7 6 2 1
+1 3 2 4 5
+8 6 4 4 1
+1 3 6 7 9";
+
     #[test]
     fn test_process() -> miette::Result<()> {
-        todo!("haven't built test yet");
-        let input = "";
-        assert_eq!("", process(input)?);
+        assert_eq!("4", process(TEST1)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_dampened_levels() -> miette::Result<()> {
+        use Verdict::*;
+
+        let expected = vec![Safe, Unsafe, Unsafe, Dampened(1), Dampened(2), Safe];
+        for variant in Variant::ALL {
+            assert_eq!(expected, verdicts(TEST1, variant)?, "{variant:?}");
+        }
+        // first and last levels
+        assert_eq!(dampen(&[9, 1, 2, 3]), Dampened(0));
+        assert_eq!(dampen(&[1, 2, 3, 9]), Dampened(3));
+        assert_eq!(dampen(&[5, 4]), Safe);
+        assert_eq!(dampen(&[1, 1]), Dampened(0));
+        assert_eq!(dampen(&[]), Safe);
+        Ok(())
+    }
+
+    #[test]
+    fn test_variants_agree() -> miette::Result<()> {
+        let input = include_str!("../input2.txt");
+        assert_eq!(
+            verdicts(input, Variant::Linear)?,
+            verdicts(input, Variant::Quadratic)?
+        );
         Ok(())
     }
 }
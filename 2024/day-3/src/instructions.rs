@@ -0,0 +1,144 @@
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{char, digit1},
+    combinator::{map_res, value, verify},
+    sequence::{delimited, separated_pair},
+    IResult, Parser,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Instruction {
+    Mul(u32, u32),
+    Do,
+    Dont,
+}
+
+/// 1 to 3 digits, nothing else.
+fn operand(input: &str) -> IResult<&str, u32> {
+    map_res(verify(digit1, |d: &str| d.len() <= 3), str::parse::<u32>).parse(input)
+}
+
+/// `mul(X,Y)`, with no space anywhere.
+fn mul(input: &str) -> IResult<&str, Instruction> {
+    delimited(
+        tag("mul("),
+        separated_pair(operand, char(','), operand),
+        char(')'),
+    )
+    .map(|(a, b)| Instruction::Mul(a, b))
+    .parse(input)
+}
+
+/// A well formed instruction right at the start of `input`.
+pub fn instruction(input: &str) -> IResult<&str, Instruction> {
+    alt((
+        mul,
+        value(Instruction::Do, tag("do()")),
+        value(Instruction::Dont, tag("don't()")),
+    ))
+    .parse(input)
+}
+
+/// Every well formed instruction of the corrupted memory, in order.
+///
+/// Where no instruction starts, a single char is skipped, so `mul(mul(2,3)`
+/// still yields the inner `mul(2,3)`.
+pub fn tokenize(memory: &str) -> impl Iterator<Item = Instruction> + '_ {
+    let mut rest = memory;
+    std::iter::from_fn(move || loop {
+        if let Ok((after, found)) = instruction(rest) {
+            rest = after;
+            return Some(found);
+        }
+        let mut chars = rest.chars();
+        chars.next()?;
+        rest = chars.as_str();
+    })
+}
+
+/// Runs the instructions, `do()` and `don't()` switching the `mul`s on and
+/// off when `toggles` is set.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Machine {
+    pub toggles: bool,
+    pub enabled: bool,
+    pub sum: u64,
+}
+
+impl Machine {
+    pub fn new(toggles: bool) -> Self {
+        Self {
+            toggles,
+            enabled: true,
+            sum: 0,
+        }
+    }
+
+    pub fn execute(&mut self, instruction: Instruction) {
+        match instruction {
+            Instruction::Mul(a, b) if self.enabled => self.sum += a as u64 * b as u64,
+            Instruction::Mul(..) => {}
+            Instruction::Do => self.enabled = true,
+            Instruction::Dont => self.enabled = !self.toggles,
+        }
+    }
+
+    /// Sum of the enabled products once every instruction has run.
+    pub fn run(mut self, instructions: impl IntoIterator<Item = Instruction>) -> u64 {
+        for instruction in instructions {
+            self.execute(instruction);
+        }
+        self.sum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Instruction::*;
+
+    fn tokens(memory: &str) -> Vec<Instruction> {
+        tokenize(memory).collect()
+    }
+
+    #[test]
+    fn test_well_formed() {
+        assert_eq!(tokens("mul(44,46)"), vec![Mul(44, 46)]);
+        assert_eq!(tokens("mul(123,4)"), vec![Mul(123, 4)]);
+        assert_eq!(tokens("do()don't()"), vec![Do, Dont]);
+        assert_eq!(
+            tokens("xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"),
+            vec![Mul(2, 4), Dont, Mul(5, 5), Mul(11, 8), Do, Mul(8, 5)]
+        );
+    }
+
+    #[test]
+    fn test_malformed() {
+        assert_eq!(tokens("mul(4*"), vec![]);
+        assert_eq!(tokens("mul(6,9!"), vec![]);
+        assert_eq!(tokens("?(12,34)"), vec![]);
+        assert_eq!(tokens("mul ( 2 , 4 )"), vec![]);
+        assert_eq!(tokens("mul(2, 4)"), vec![]);
+        assert_eq!(tokens("mul(1234,5)"), vec![]);
+        assert_eq!(tokens("mul(-2,4)"), vec![]);
+        assert_eq!(tokens("mul(2,4"), vec![]);
+        assert_eq!(tokens("do ()don't"), vec![]);
+        assert!(instruction("xmul(2,4)").is_err());
+    }
+
+    #[test]
+    fn test_recovers_inside_garbage() {
+        assert_eq!(tokens("mul(mul(2,3)"), vec![Mul(2, 3)]);
+        assert_eq!(tokens("mul(4*mul(5,6)"), vec![Mul(5, 6)]);
+        assert_eq!(tokens("don't(do()"), vec![Do]);
+        assert_eq!(tokens("é mul(1,2) ü"), vec![Mul(1, 2)]);
+    }
+
+    #[test]
+    fn test_machine() {
+        let program = [Mul(2, 4), Dont, Mul(5, 5), Do, Mul(8, 5)];
+        assert_eq!(Machine::new(false).run(program), 73);
+        assert_eq!(Machine::new(true).run(program), 48);
+    }
+}
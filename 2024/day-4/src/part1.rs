@@ -1,17 +1,31 @@
+use aoc_utils::grid::Grid2D;
+use aoc_utils::pattern::find_word;
+
 #[tracing::instrument]
-pub fn process(_input: &str) -> miette::Result<String> {
-    todo!("day 01 - part 1");
+pub fn process(input: &str) -> miette::Result<String> {
+    let grid = Grid2D::parse_with(input, |ch| ch);
+    let word: Vec<char> = "XMAS".chars().collect();
+    Ok(find_word(&grid, &word).len().to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const TEST1: &str = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
     #[test]
     fn test_process() -> miette::Result<()> {
-        todo!("haven't built test yet");
-        let input = "";
-        assert_eq!("", process(input)?);
+        assert_eq!("18", process(TEST1)?);
         Ok(())
     }
 }
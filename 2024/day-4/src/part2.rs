@@ -1,17 +1,43 @@
+use aoc_utils::grid::Grid2D;
+use aoc_utils::pattern::{find_kernel, parse_kernel};
+
+/// Two `MAS` crossing on their `A`, either way round.
+const X_MAS: &str = "M.S
+.A.
+M.S";
+
 #[tracing::instrument]
-pub fn process(_input: &str) -> miette::Result<String> {
-    todo!("day 01 - part 2");
+pub fn process(input: &str) -> miette::Result<String> {
+    let grid = Grid2D::parse_with(input, |ch| ch);
+    let kernel = parse_kernel(X_MAS, '.');
+    Ok(find_kernel(&grid, &kernel).len().to_string())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    const TEST1: &str = "MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
     #[test]
     fn test_process() -> miette::Result<()> {
-        todo!("haven't built test yet");
-        let input = "";
-        assert_eq!("", process(input)?);
+        assert_eq!("9", process(TEST1)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_cross_is_not_a_plus() -> miette::Result<()> {
+        assert_eq!("0", process(".M.\nMAS\n.S.")?);
+        assert_eq!("1", process("S.S\n.A.\nM.M")?);
         Ok(())
     }
 }
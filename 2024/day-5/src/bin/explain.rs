@@ -0,0 +1,14 @@
+use day_5::explain::explain;
+use miette::Context;
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let file = include_str!("../../input1.txt");
+    let reports = explain(file).context("explain input")?;
+    for report in reports {
+        println!("{:?}", miette::Report::new(report));
+    }
+    Ok(())
+}
@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+
+use miette::{Diagnostic, LabeledSpan, NamedSource, Result, SourceSpan};
+use thiserror::Error;
+
+use crate::part1::{check_rule, input_to_rules_and_pages, Rule};
+use crate::part2::sort_single_page_list;
+use crate::topo::Graph;
+
+/// A rule `a|b` broken by an update: page `a` sits after page `b`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Violation {
+    pub rule: Rule,
+    /// Index of `a` in the update.
+    pub early: usize,
+    /// Index of `b` in the update, before `early`.
+    pub late: usize,
+}
+
+/// Every rule the update breaks, by index of the misplaced page.
+pub fn violations(rules: &Graph<i32>, pages: &[i32]) -> Vec<Violation> {
+    let pages_vec = pages.to_vec();
+    let mut found: Vec<Violation> = rules
+        .iter()
+        .flat_map(|(&a, targets)| targets.iter().map(move |&b| (a, b)))
+        .filter(|rule| !check_rule(rule, &pages_vec))
+        .filter_map(|rule| {
+            let early = pages.iter().position(|&p| p == rule.0)?;
+            let late = pages.iter().position(|&p| p == rule.1)?;
+            Some(Violation { rule, early, late })
+        })
+        .collect();
+    found.sort_by_key(|v| (v.early, v.late));
+    found
+}
+
+/// Fewest swaps of two pages turning `pages` into `sorted`, a permutation of
+/// it, as pairs of indices applied in order.
+///
+/// Every cycle of the permutation of length `k` takes `k - 1` swaps, and no
+/// less is possible.
+pub fn minimal_swaps(pages: &[i32], sorted: &[i32]) -> Vec<(usize, usize)> {
+    let mut current = pages.to_vec();
+    let mut swaps = Vec::new();
+    for i in 0..current.len() {
+        while current[i] != sorted[i] {
+            let j = current
+                .iter()
+                .position(|&p| p == sorted[i])
+                .expect("sorted is a permutation of pages");
+            current.swap(i, j);
+            swaps.push((i, j));
+        }
+    }
+    swaps
+}
+
+/// An update out of order, with the rules it breaks and how to fix it.
+#[derive(Debug, Error, Diagnostic)]
+#[error("update on line {line} breaks {} ordering rule(s)", violations.len())]
+#[diagnostic(code(day_5::unordered_update), help("{help}"))]
+pub struct UnorderedUpdate {
+    /// 1-based line of the update in the input.
+    pub line: usize,
+    pub violations: Vec<Violation>,
+    pub corrected: Vec<i32>,
+    pub swaps: Vec<(usize, usize)>,
+    #[source_code]
+    src: NamedSource<String>,
+    #[label(collection)]
+    labels: Vec<LabeledSpan>,
+    help: String,
+}
+
+/// Span of every page of every update, in input order.
+fn update_spans(input: &str) -> Vec<(usize, Vec<SourceSpan>)> {
+    let mut updates = Vec::new();
+    let mut offset = 0;
+    let mut in_updates = false;
+    for (i, line) in input.split_inclusive('\n').enumerate() {
+        let content = line.trim_end();
+        if content.is_empty() {
+            in_updates = true;
+        } else if in_updates {
+            let mut start = offset;
+            let spans = content
+                .split(',')
+                .map(|page| {
+                    let span = SourceSpan::from((start, page.len()));
+                    start += page.len() + 1;
+                    span
+                })
+                .collect();
+            updates.push((i + 1, spans));
+        }
+        offset += line.len();
+    }
+    updates
+}
+
+/// One diagnostic per update breaking a rule, labelling each misplaced page.
+pub fn explain(input: &str) -> Result<Vec<UnorderedUpdate>> {
+    let (rules, pages_list) = input_to_rules_and_pages(input)?;
+    let src = NamedSource::new("input", input.to_string());
+    let spans = update_spans(input);
+
+    let mut reports = Vec::new();
+    for (pages, (line, page_spans)) in pages_list.iter().zip(spans) {
+        let violations = violations(&rules, pages);
+        if violations.is_empty() {
+            continue;
+        }
+        let corrected = sort_single_page_list(&rules, pages);
+        let swaps = minimal_swaps(pages, &corrected);
+
+        // the pages each misplaced page should have come before
+        let mut must_precede: BTreeMap<usize, Vec<String>> = BTreeMap::new();
+        for v in &violations {
+            must_precede
+                .entry(v.early)
+                .or_default()
+                .push(format!("{} (#{})", v.rule.1, v.late));
+        }
+        let labels = must_precede
+            .into_iter()
+            .map(|(early, pages)| {
+                LabeledSpan::new_with_span(
+                    Some(format!("must come before {}", pages.join(", "))),
+                    page_spans[early],
+                )
+            })
+            .collect();
+
+        let swap_list: Vec<String> = swaps
+            .iter()
+            .map(|&(i, j)| format!("#{i} <-> #{j}"))
+            .collect();
+        let help = format!(
+            "corrected order: {}\n{} swap(s): {}",
+            corrected
+                .iter()
+                .map(|p| p.to_string())
+                .collect::<Vec<_>>()
+                .join(","),
+            swaps.len(),
+            swap_list.join(", ")
+        );
+
+        reports.push(UnorderedUpdate {
+            line,
+            violations,
+            corrected,
+            swaps,
+            src: src.clone(),
+            labels,
+            help,
+        });
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1::parse_rules_and_pages;
+
+    #[test]
+    fn test_violations() {
+        let (_, (rules, _)) = parse_rules_and_pages(INPUT).unwrap();
+        assert_eq!(
+            violations(&rules, &[75, 97, 47, 61, 53]),
+            vec![Violation {
+                rule: (97, 75),
+                early: 1,
+                late: 0
+            }]
+        );
+        let rules_broken: Vec<Rule> = violations(&rules, &[61, 13, 29])
+            .iter()
+            .map(|v| v.rule)
+            .collect();
+        assert_eq!(rules_broken, vec![(29, 13)]);
+        assert!(violations(&rules, &[75, 47, 61, 53, 29]).is_empty());
+    }
+
+    #[test]
+    fn test_minimal_swaps() {
+        assert_eq!(minimal_swaps(&[75, 97, 47], &[97, 75, 47]), vec![(0, 1)]);
+        // one 3-cycle and a fixed point
+        let swaps = minimal_swaps(&[97, 13, 75, 29, 47], &[97, 75, 47, 29, 13]);
+        assert_eq!(swaps, vec![(1, 2), (2, 4)]);
+        assert!(minimal_swaps(&[1, 2], &[1, 2]).is_empty());
+    }
+
+    #[test]
+    fn test_explain() -> Result<()> {
+        let reports = explain(INPUT)?;
+        let lines: Vec<usize> = reports.iter().map(|r| r.line).collect();
+        assert_eq!(lines, vec![26, 27, 28]);
+        assert_eq!(reports[2].corrected, vec![97, 75, 47, 29, 13]);
+        assert_eq!(reports[2].swaps.len(), 2);
+
+        let mut rendered = String::new();
+        miette::GraphicalReportHandler::new_themed(miette::GraphicalTheme::unicode_nocolor())
+            .render_report(&mut rendered, &reports[0])
+            .unwrap();
+        assert!(rendered.contains("update on line 26 breaks 1 ordering rule(s)"));
+        assert!(rendered.contains("75,97,47,61,53"));
+        assert!(rendered.contains("must come before 75 (#0)"));
+        assert!(rendered.contains("corrected order: 97,75,47,61,53"));
+        Ok(())
+    }
+
+    const INPUT: &str = "47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47";
+}
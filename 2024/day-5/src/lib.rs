@@ -1,3 +1,5 @@
+pub mod explain;
+pub mod order;
 pub mod part1;
 pub mod part2;
 pub mod topo;
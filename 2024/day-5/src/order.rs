@@ -0,0 +1,217 @@
+use std::cmp::Ordering;
+
+use miette::{miette, Result};
+
+use crate::topo::Graph;
+
+/// Page ids must be below this to fit a row of the adjacency.
+pub const MAX_PAGE: usize = 128;
+
+/// The ordering rules as a bitset adjacency: bit `b` of `before[a]` and bit
+/// `a` of `after[b]` are set when a rule `a|b` exists.
+///
+/// The rules as a whole are cyclic, so this is no order on all pages, but it
+/// is a total one on an update whose induced rules form a transitive
+/// tournament, which [`PageOrder::is_tournament`] checks.
+#[derive(Clone, Debug)]
+pub struct PageOrder {
+    before: [u128; MAX_PAGE],
+    after: [u128; MAX_PAGE],
+}
+
+impl PageOrder {
+    pub fn new(rules: &Graph<i32>) -> Result<Self> {
+        let mut before = [0u128; MAX_PAGE];
+        let mut after = [0u128; MAX_PAGE];
+        for (&a, targets) in rules {
+            let a = index(a)?;
+            for &b in targets {
+                let b = index(b)?;
+                before[a] |= 1 << b;
+                after[b] |= 1 << a;
+            }
+        }
+        Ok(Self { before, after })
+    }
+
+    /// True if a rule puts `a` before `b`. Pages out of range have no rules.
+    pub fn precedes(&self, a: i32, b: i32) -> bool {
+        row(&self.before, a) & bit(b) != 0
+    }
+
+    /// `Less` if `a` must come before `b`, `Greater` if after, `Equal` when no
+    /// rule relates them.
+    pub fn compare(&self, a: i32, b: i32) -> Ordering {
+        if self.precedes(a, b) {
+            Ordering::Less
+        } else if self.precedes(b, a) {
+            Ordering::Greater
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    /// The pages of an update as a bitset, leaving out those out of range.
+    pub fn mask(pages: &[i32]) -> u128 {
+        pages.iter().fold(0, |mask, &p| mask | bit(p))
+    }
+
+    /// True if exactly one rule relates every two distinct pages of the
+    /// update, and the rules among them have no cycle.
+    ///
+    /// A tournament is acyclic exactly when its pages all have distinct
+    /// out-degrees, so the whole check is one popcount per page.
+    pub fn is_tournament(&self, pages: &[i32]) -> bool {
+        if pages.iter().any(|&p| index(p).is_err()) {
+            // no rule relates a page out of range to any other
+            return pages.len() < 2;
+        }
+        let mask = Self::mask(pages);
+        let n = mask.count_ones();
+        let mut scores = 0u128;
+        for &p in pages {
+            let out = row(&self.before, p) & mask;
+            let into = row(&self.after, p) & mask;
+            if out & into != 0 || (out | into).count_ones() + 1 != n {
+                return false;
+            }
+            scores |= 1 << out.count_ones();
+        }
+        scores.count_ones() == n
+    }
+
+    /// True if no two pages of the update break a rule, adjacent or not.
+    pub fn is_sorted(&self, pages: &[i32]) -> bool {
+        let mut earlier = 0u128;
+        for &p in pages {
+            if row(&self.before, p) & earlier != 0 {
+                return false;
+            }
+            earlier |= bit(p);
+        }
+        true
+    }
+
+    /// Sorts the update with [`PageOrder::compare`].
+    pub fn sort(&self, pages: &mut [i32]) {
+        pages.sort_unstable_by(|&a, &b| self.compare(a, b));
+    }
+}
+
+fn index(page: i32) -> Result<usize> {
+    usize::try_from(page)
+        .ok()
+        .filter(|&p| p < MAX_PAGE)
+        .ok_or_else(|| miette!("page {page} out of range 0..{MAX_PAGE}"))
+}
+
+/// The single bit of `page`, none if it is out of range.
+fn bit(page: i32) -> u128 {
+    index(page).map_or(0, |p| 1 << p)
+}
+
+/// The row of `page` in `rows`, empty if it is out of range.
+fn row(rows: &[u128; MAX_PAGE], page: i32) -> u128 {
+    index(page).map_or(0, |p| rows[p])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::part1::input_to_rules_and_pages;
+    use crate::topo::add_edge;
+
+    #[test]
+    fn test_page_order() -> Result<()> {
+        let (rules, pages_list) = input_to_rules_and_pages(INPUT)?;
+        let order = PageOrder::new(&rules)?;
+        assert!(order.precedes(97, 75));
+        assert_eq!(order.compare(75, 97), Ordering::Greater);
+        for pages in &pages_list {
+            assert!(order.is_tournament(pages));
+        }
+        let sorted: Vec<bool> = pages_list.iter().map(|p| order.is_sorted(p)).collect();
+        assert_eq!(sorted, vec![true, true, true, false, false, false]);
+
+        let mut pages = vec![97, 13, 75, 29, 47];
+        order.sort(&mut pages);
+        assert_eq!(pages, vec![97, 75, 47, 29, 13]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_not_tournament() -> Result<()> {
+        let mut rules = Graph::new();
+        add_edge(&mut rules, 1, 2);
+        add_edge(&mut rules, 2, 3);
+        let order = PageOrder::new(&rules)?;
+        // 1 and 3 are unrelated
+        assert!(!order.is_tournament(&[1, 2, 3]));
+        assert!(order.is_tournament(&[1, 2]));
+
+        // rock, paper, scissors
+        add_edge(&mut rules, 3, 1);
+        assert!(!PageOrder::new(&rules)?.is_tournament(&[1, 2, 3]));
+        add_edge(&mut rules, 1, 3);
+        assert!(!PageOrder::new(&rules)?.is_tournament(&[1, 3]));
+
+        add_edge(&mut rules, 1, 128);
+        assert!(PageOrder::new(&rules).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sorted_every_pair() -> Result<()> {
+        // no rule between neighbours, but 3 must come before 1
+        let mut rules = Graph::new();
+        add_edge(&mut rules, 3, 1);
+        let order = PageOrder::new(&rules)?;
+        assert!(!order.is_sorted(&[1, 2, 3]));
+        assert!(order.is_sorted(&[3, 2, 1]));
+        assert!(!order.is_tournament(&[1, 2, 3]));
+        Ok(())
+    }
+
+    #[test]
+    fn test_out_of_range() -> Result<()> {
+        let mut rules = Graph::new();
+        add_edge(&mut rules, 1, 2);
+        let order = PageOrder::new(&rules)?;
+        assert!(!order.precedes(1, 200));
+        assert!(!order.precedes(-1, 2));
+        assert_eq!(PageOrder::mask(&[1, 200, -3]), 0b10);
+        assert!(order.is_sorted(&[200, 1, -3, 2]));
+        assert!(!order.is_tournament(&[1, 2, 200]));
+        assert!(order.is_tournament(&[200]));
+        Ok(())
+    }
+
+    const INPUT: &str = "47|53
+97|13
+97|61
+97|47
+75|29
+61|13
+75|53
+29|13
+97|29
+53|29
+61|53
+97|53
+61|29
+47|13
+75|47
+97|75
+47|61
+75|61
+47|29
+75|13
+53|13
+
+75,47,61,53,29
+97,61,53,29,13
+75,29,13
+75,97,47,61,53
+61,13,29
+97,13,75,29,47";
+}
@@ -77,7 +77,7 @@ impl From<Graph<i32>> for State<i32> {
 
         // Seed zero-indegree nodes (no incoming edges in depends_on)
         for n in nodes {
-            if st.depends_on.get(&n).map_or(true, |s| s.is_empty()) {
+            if st.depends_on.get(&n).is_none_or(|s| s.is_empty()) {
                 st.no_deps.push(n);
             }
         }
@@ -0,0 +1,232 @@
+use std::collections::HashSet;
+
+use crate::part1::{turn_right, Dir, State, ORDER};
+
+/// Where the guard is and which way they face, `cell` being
+/// `line * columns + column`.
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Guard {
+    pub cell: usize,
+    pub dir: Dir,
+}
+
+/// For every cell and heading, the cell where the guard stops in front of
+/// the next wall, or `None` if they walk off the map first.
+///
+/// The guard then moves wall to wall instead of cell to cell, and adding an
+/// obstruction only rewrites the stops of the cells it now shadows.
+#[derive(Debug, Clone)]
+pub struct JumpTable {
+    lines: usize,
+    columns: usize,
+    walls: Vec<bool>,
+    stops: [Vec<Option<usize>>; 4],
+}
+
+/// The stops an obstruction overwrote, to take it back with
+/// [`JumpTable::remove`].
+#[derive(Debug)]
+pub struct Patch {
+    cell: usize,
+    placed: bool,
+    old: Vec<(Dir, usize, Option<usize>)>,
+}
+
+impl JumpTable {
+    /// The table of the map and the guard's start.
+    pub fn parse(input: &str) -> (Self, Guard) {
+        let lines = input.lines().count();
+        let columns = input.lines().next().map_or(0, str::len);
+        let mut table = Self {
+            lines,
+            columns,
+            walls: vec![false; lines * columns],
+            stops: std::array::from_fn(|_| vec![None; lines * columns]),
+        };
+        let mut guard = None;
+        for (cell, ch) in input.lines().flat_map(str::chars).enumerate() {
+            match State::from(ch) {
+                State::Wall => {
+                    table.place(cell);
+                }
+                State::Guard(dir) => guard = Some(Guard { cell, dir }),
+                State::Ground(_) => {}
+            }
+        }
+        (table, guard.expect("no guard found"))
+    }
+
+    pub fn len(&self) -> usize {
+        self.walls.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.walls.is_empty()
+    }
+
+    pub fn is_wall(&self, cell: usize) -> bool {
+        self.walls[cell]
+    }
+
+    /// The cell next to `cell` heading `dir`, if on the map.
+    pub fn step(&self, cell: usize, dir: Dir) -> Option<usize> {
+        let (line, column) = (cell / self.columns, cell % self.columns);
+        match dir {
+            Dir::Up => (line > 0).then(|| cell - self.columns),
+            Dir::Right => (column + 1 < self.columns).then_some(cell + 1),
+            Dir::Down => (line + 1 < self.lines).then_some(cell + self.columns),
+            Dir::Left => (column > 0).then(|| cell - 1),
+        }
+    }
+
+    /// Where the guard stops heading `dir` from `cell`, `None` off the map.
+    pub fn stop(&self, cell: usize, dir: Dir) -> Option<usize> {
+        self.stops[dir as usize][cell]
+    }
+
+    /// Turns `cell` into a wall. Every cell behind it, up to the previous
+    /// wall, now stops right in front of it.
+    pub fn place(&mut self, cell: usize) -> Patch {
+        let mut old = Vec::new();
+        let placed = !self.walls[cell];
+        if placed {
+            self.walls[cell] = true;
+            for dir in ORDER {
+                let back = turn_right(turn_right(dir));
+                let Some(front) = self.step(cell, back) else {
+                    continue;
+                };
+                let mut behind = Some(front);
+                while let Some(c) = behind.filter(|&c| !self.walls[c]) {
+                    old.push((dir, c, self.stops[dir as usize][c]));
+                    self.stops[dir as usize][c] = Some(front);
+                    behind = self.step(c, back);
+                }
+            }
+        }
+        Patch { cell, placed, old }
+    }
+
+    /// Takes back a [`JumpTable::place`], the latest first.
+    pub fn remove(&mut self, patch: Patch) {
+        if !patch.placed {
+            return;
+        }
+        for (dir, c, stop) in patch.old {
+            self.stops[dir as usize][c] = stop;
+        }
+        self.walls[patch.cell] = false;
+    }
+
+    /// The guard at each wall they turn at, starting from `start`, and
+    /// whether they end up walking in a loop rather than off the map.
+    pub fn legs(&self, start: Guard) -> (Vec<Guard>, bool) {
+        let mut legs = vec![start];
+        let mut turns = HashSet::new();
+        let mut guard = start;
+        while let Some(cell) = self.stop(guard.cell, guard.dir) {
+            guard = Guard {
+                cell,
+                dir: turn_right(guard.dir),
+            };
+            if !turns.insert(guard) {
+                return (legs, true);
+            }
+            legs.push(guard);
+        }
+        (legs, false)
+    }
+
+    /// True if the guard from `start` never leaves the map.
+    pub fn loops(&self, start: Guard) -> bool {
+        let mut turns = HashSet::new();
+        let mut guard = start;
+        while let Some(cell) = self.stop(guard.cell, guard.dir) {
+            if !turns.insert((cell, guard.dir)) {
+                return true;
+            }
+            guard = Guard {
+                cell,
+                dir: turn_right(guard.dir),
+            };
+        }
+        false
+    }
+
+    /// Every distinct cell the guard from `start` walks on, in the order
+    /// first reached, expanding the legs back into single steps.
+    pub fn route(&self, start: Guard) -> Vec<usize> {
+        let (legs, _) = self.legs(start);
+        let mut seen = vec![false; self.len()];
+        let mut route = Vec::new();
+        for leg in legs {
+            let end = self.stop(leg.cell, leg.dir);
+            let mut cell = Some(leg.cell);
+            while let Some(c) = cell {
+                if !seen[c] {
+                    seen[c] = true;
+                    route.push(c);
+                }
+                if Some(c) == end {
+                    break;
+                }
+                cell = self.step(c, leg.dir);
+            }
+        }
+        route
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stops() {
+        let (table, guard) = JumpTable::parse(TEST_INPUT);
+        assert_eq!(
+            guard,
+            Guard {
+                cell: 64,
+                dir: Dir::Up
+            }
+        );
+        // up from the guard to below the wall at (0, 4)
+        assert_eq!(table.stop(64, Dir::Up), Some(14));
+        assert_eq!(table.stop(14, Dir::Right), Some(18));
+        assert_eq!(table.stop(64, Dir::Left), Some(62));
+        assert_eq!(table.stop(64, Dir::Down), None);
+        assert_eq!(table.route(guard).len(), 41);
+        assert!(!table.loops(guard));
+    }
+
+    #[test]
+    fn test_place_and_remove() {
+        let (mut table, guard) = JumpTable::parse(TEST_INPUT);
+        let fresh = table.clone();
+        // the example's first looping obstruction, left of the guard
+        let patch = table.place(63);
+        assert_eq!(table.stop(65, Dir::Left), Some(64));
+        assert_eq!(table.stop(53, Dir::Down), Some(53));
+        assert!(table.loops(guard));
+        table.remove(patch);
+        assert_eq!(table.stops, fresh.stops);
+        assert_eq!(table.walls, fresh.walls);
+
+        // walls already there are left alone
+        let patch = table.place(4);
+        table.remove(patch);
+        assert!(table.is_wall(4));
+    }
+
+    const TEST_INPUT: &str = "....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#...";
+}
@@ -26,7 +26,7 @@ pub fn process(input: &str) -> miette::Result<String> {
 
     let sum: usize = pos_map
         .iter()
-        .filter(|(vpos, state)| state.ground_is_true())
+        .filter(|(_, state)| state.ground_is_true())
         .count();
 
     Ok(sum.to_string())
@@ -130,7 +130,7 @@ pub enum Dir {
 
 #[inline]
 pub fn turn_right(d: Dir) -> Dir {
-    ORDER[(ORDER.iter().position(|x| *x == d).unwrap() + 1) & 3]
+    ORDER[(d as usize + 1) & 3]
 }
 
 impl From<char> for State {
@@ -172,6 +172,7 @@ pub fn parse_map(input: &str) -> Vec<Vec<State>> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::jump::JumpTable;
     use crate::part1::State::Guard;
 
     #[test]
@@ -181,6 +182,15 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_jump_table_route() -> miette::Result<()> {
+        // the legs expanded back into cells visit the same ones as the trace
+        let input = include_str!("../input1.txt");
+        let (table, start) = JumpTable::parse(input);
+        assert_eq!(process(input)?, table.route(start).len().to_string());
+        Ok(())
+    }
+
     #[test]
     fn test_grid() -> miette::Result<()> {
         let input = TEST_INPUT;
@@ -0,0 +1,36 @@
+use day_7::*;
+
+fn main() {
+    // Run registered benchmarks.
+    divan::main();
+}
+
+#[divan::bench]
+fn part1() {
+    part1::process(divan::black_box(include_str!("../input1.txt"))).unwrap();
+}
+
+#[divan::bench]
+fn part1_bfs() {
+    solver::process_with(
+        divan::black_box(include_str!("../input1.txt")),
+        &bfs::ADD_MUL,
+        solver::Variant::Bfs,
+    )
+    .unwrap();
+}
+
+#[divan::bench]
+fn part2() {
+    part2::process(divan::black_box(include_str!("../input2.txt"))).unwrap();
+}
+
+#[divan::bench]
+fn part2_bfs() {
+    solver::process_with(
+        divan::black_box(include_str!("../input2.txt")),
+        &bfs::ADD_MUL_CONCAT,
+        solver::Variant::Bfs,
+    )
+    .unwrap();
+}
@@ -1,27 +1,100 @@
-use std::{
-    collections::{HashMap, HashSet, VecDeque},
-    hash::Hash,
-    iter::Sum,
-};
+use core::fmt;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use aoc_utils::math::{concat, strip_digits};
+
+use crate::number::Number;
+
+/// An operator between two numbers of an equation, always evaluated left to
+/// right.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy)]
+pub enum Op {
+    Add,
+    Mul,
+    Concat,
+}
+
+/// The operators of part 1.
+pub const ADD_MUL: [Op; 2] = [Op::Add, Op::Mul];
+/// The operators of part 2.
+pub const ADD_MUL_CONCAT: [Op; 3] = [Op::Add, Op::Mul, Op::Concat];
+
+impl Op {
+    /// `a op b`, `None` if it overflows `N`.
+    pub fn apply<N: Number>(self, a: N, b: N) -> Option<N> {
+        match self {
+            Op::Add => a.checked_add(b),
+            Op::Mul => a.checked_mul(b),
+            Op::Concat => concat(a, b),
+        }
+    }
+
+    /// Same as [`Op::apply`] where `None` stands for any `a` too large for
+    /// `N`. Only a product with 0 brings it back, everything else stays too
+    /// large, so a branch that overflowed never reaches a goal again.
+    pub fn step<N: Number>(self, a: Option<N>, b: N) -> Option<N> {
+        match a {
+            Some(a) => self.apply(a, b),
+            None => (self == Op::Mul && b == N::ZERO).then_some(N::ZERO),
+        }
+    }
+
+    /// The `a` for which `a op b` is `res`, if there is exactly one.
+    ///
+    /// Subtraction only applies when it stays non-negative, division when
+    /// `b` divides `res`, and un-concatenation when `res` ends with the
+    /// digits of `b`. Multiplying by 0 gives 0 for every `a`, so has none.
+    pub fn unapply<N: Number>(self, res: N, b: N) -> Option<N> {
+        match self {
+            Op::Add => res.checked_sub(b),
+            Op::Mul => res.exact_div(b),
+            Op::Concat => strip_digits(res, b),
+        }
+    }
+}
+
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Op::Add => write!(f, "➕"),
+            Op::Mul => write!(f, "✖️"),
+            Op::Concat => write!(f, "➰"),
+        }
+    }
+}
+
+/// `nums` folded left to right with `ops`, one fewer than the numbers, or
+/// `None` if the result doesn't fit `N`.
+pub fn evaluate<N: Number>(nums: &[N], ops: &[Op]) -> Option<N> {
+    let (first, rest) = nums.split_first().expect("no numbers to evaluate");
+    // not a `try_fold`, a product with 0 recovers from `None`
+    let mut acc = Some(*first);
+    for (&n, op) in rest.iter().zip(ops) {
+        acc = op.step(acc, n);
+    }
+    acc
+}
 
-use crate::part1::Op;
 #[derive(PartialEq, Eq, Clone, Copy, Hash, Debug)]
-pub struct Key {
+pub struct Key<N> {
     depth: usize,
-    res: u64,
+    /// `None` past the top of `N`, see [`Op::step`].
+    res: Option<N>,
 }
 
-pub fn chech_lines(input: Vec<(u64, Vec<u64>)>) -> u64 {
-    dbg!(
-        input
-            .iter()
-            .filter_map(|(goal, nums)| check_line(*goal, nums).then_some(goal))
-            .sum()
-    )
+/// Sum of the goals that some choice among `ops` reaches.
+pub fn check_lines<N: Number>(input: Vec<(N, Vec<N>)>, ops: &[Op]) -> u128 {
+    input
+        .iter()
+        .filter_map(|(goal, nums)| check_line(*goal, nums, ops).then_some((*goal).into()))
+        .sum()
 }
 
 // Remontada
-pub fn remontada(start: &Key, parents: HashMap<Key, Option<(Key, Op)>>) -> Vec<Op> {
+pub fn remontada<N: Number>(
+    start: &Key<N>,
+    parents: HashMap<Key<N>, Option<(Key<N>, Op)>>,
+) -> Vec<Op> {
     // 1. New Vec<node>
     let mut path = Vec::with_capacity(start.depth);
     // 2. Add solution node
@@ -33,48 +106,33 @@ pub fn remontada(start: &Key, parents: HashMap<Key, Option<(Key, Op)>>) -> Vec<O
     }
 
     path.reverse();
-    println!(
-        "{:>20}:\t {}",
-        start.res,
-        path.iter().map(|op| op.to_string()).collect::<String>()
-    );
     path
 }
 
-pub fn check_line(goal: u64, nums: &[u64]) -> bool {
+pub fn check_line<N: Number>(goal: N, nums: &[N], ops: &[Op]) -> bool {
+    solve(goal, nums, ops).is_some()
+}
+
+/// The operators, picked from `ops`, that make `nums` evaluate to `goal`.
+pub fn solve<N: Number>(goal: N, nums: &[N], ops: &[Op]) -> Option<Vec<Op>> {
     if let Some((first, rest)) = nums.split_first() {
         let start = Key {
             depth: 0,
-            res: *first,
+            res: Some(*first),
         };
         let goal_key = Key {
             depth: nums.len() - 1,
-            res: goal,
+            res: Some(goal),
         };
-
-        // HACK: These early return create false negatives.
-        // match (&goal, first, rest) {
-        //     // first is alone and goal
-        //     (g, f, r) if g == f && r.is_empty() => return true,
-        //     // first too small
-        //     (g, f, _) if g < f => return false,
-        //     //     // This is not true! 1+1 > 1*1
-        //     //     (g, f, r) if g < &f.add(r.iter().sum::<u64>()) => return false,
-        //     //     // This is not true! 2 > 1*1
-        //     //     (g, f, r) if g > &f.mul(r.iter().product::<u64>()) => return false,
-        //     (_, _, _) => (),
-        // };
-
-        // if there is at least one solution, true
-        bfs(start, rest, goal_key).is_some()
+        bfs(start, rest, goal_key, ops)
     } else {
         // if nums is empty returns false
         unreachable!("There shouldn't be an empty list of nums: {nums:?}");
     }
 }
 
-pub fn bfs(start: Key, rest: &[u64], goal: Key) -> Option<Vec<Op>> {
-    let mut q = VecDeque::<Key>::new();
+pub fn bfs<N: Number>(start: Key<N>, rest: &[N], goal: Key<N>, ops: &[Op]) -> Option<Vec<Op>> {
+    let mut q = VecDeque::<Key<N>>::new();
     let mut seen = HashSet::new();
     let mut parents = HashMap::new();
 
@@ -90,28 +148,21 @@ pub fn bfs(start: Key, rest: &[u64], goal: Key) -> Option<Vec<Op>> {
         if cur == goal {
             return Some(remontada(&cur, parents));
         }
-        if let Some(next_i) = rest.get(cur.depth) {
-            let add_neigh = Key {
-                depth: cur.depth + 1,
-                res: cur.res + next_i,
-            };
-            let mul_neigh = Key {
-                depth: cur.depth + 1,
-                res: cur.res * next_i,
-            };
-            // add neighbors to q
-            q.push_back(add_neigh);
-            q.push_back(mul_neigh);
-            // add neighbors to seen
-            seen.insert(add_neigh);
-            seen.insert(mul_neigh);
-            // update neighbors parents
-            parents.insert(add_neigh, Some((cur, Op::Add)));
-            parents.insert(mul_neigh, Some((cur, Op::Mul)));
+        if let Some(&next_i) = rest.get(cur.depth) {
+            for &op in ops {
+                let neigh = Key {
+                    depth: cur.depth + 1,
+                    res: op.step(cur.res, next_i),
+                };
+                // the first path to a key is as good as any other
+                if seen.insert(neigh) {
+                    q.push_back(neigh);
+                    parents.insert(neigh, Some((cur, op)));
+                }
+            }
         }
     }
     // q is empty and goal not found
-    println!("{:>20}: \t ❌", goal.res);
     None
 }
 
@@ -132,25 +183,87 @@ mod tests {
 
     #[test]
     fn test_bfs() {
-        let (_, lines) = parse(INPUT_TEST).unwrap();
-        assert!(check_line(lines[0].0, &lines[0].1));
-        assert!(check_line(lines[1].0, &lines[1].1));
-        assert!(!check_line(lines[2].0, &lines[2].1));
-        assert!(!check_line(lines[3].0, &lines[3].1));
-        assert!(!check_line(lines[4].0, &lines[4].1));
-        assert!(!check_line(lines[5].0, &lines[5].1));
-        assert!(!check_line(lines[6].0, &lines[6].1));
-        assert!(!check_line(lines[7].0, &lines[7].1));
-        assert!(check_line(lines[8].0, &lines[8].1));
-        assert_eq!(chech_lines(lines), 3749);
+        let (_, lines) = parse::<u64>(INPUT_TEST).unwrap();
+        let solvable: Vec<bool> = lines
+            .iter()
+            .map(|(goal, nums)| check_line(*goal, nums, &ADD_MUL))
+            .collect();
+        assert_eq!(
+            solvable,
+            [true, true, false, false, false, false, false, false, true]
+        );
+        let solvable: Vec<bool> = lines
+            .iter()
+            .map(|(goal, nums)| check_line(*goal, nums, &ADD_MUL_CONCAT))
+            .collect();
+        assert_eq!(
+            solvable,
+            [true, true, false, true, true, false, true, false, true]
+        );
+        assert_eq!(check_lines(lines.clone(), &ADD_MUL), 3749);
+        assert_eq!(check_lines(lines, &ADD_MUL_CONCAT), 11387);
+    }
+
+    #[test]
+    fn test_witness() {
+        assert_eq!(solve(190u64, &[10, 19], &ADD_MUL), Some(vec![Op::Mul]));
+        assert_eq!(
+            solve(7290u64, &[6, 8, 6, 15], &ADD_MUL_CONCAT),
+            Some(vec![Op::Mul, Op::Concat, Op::Mul])
+        );
+        let ops = solve(292u64, &[11, 6, 16, 20], &ADD_MUL).unwrap();
+        assert_eq!(evaluate(&[11u64, 6, 16, 20], &ops), Some(292));
+        assert_eq!(solve(156u64, &[15, 6], &ADD_MUL), None);
+    }
+
+    #[test]
+    fn test_apply() {
+        assert_eq!(Op::Concat.apply(12u64, 345), Some(12345));
+        assert_eq!(Op::Concat.apply(1u64, 0), Some(10));
+        assert_eq!(Op::Concat.apply(0u64, u64::MAX), Some(u64::MAX));
+        assert_eq!(Op::Concat.apply(2u64, u64::MAX / 10), None);
+        assert_eq!(Op::Mul.apply(u64::MAX, 2), None);
+        assert_eq!(Op::Add.step(None, 5u64), None);
+        assert_eq!(Op::Mul.step(None, 0u64), Some(0));
+    }
+
+    #[test]
+    fn test_overflow() {
+        let big = u64::MAX / 2 + 1;
+        // doubling overflows, the sum is the only way
+        assert_eq!(solve(big + 2, &[big, 2], &ADD_MUL), Some(vec![Op::Add]));
+        assert_eq!(solve(u64::MAX, &[big, 2], &ADD_MUL), None);
+        // an overflowing product wiped out by a later 0
+        assert_eq!(
+            solve(0, &[big, 4, 0], &[Op::Mul]),
+            Some(vec![Op::Mul, Op::Mul])
+        );
+        assert_eq!(
+            evaluate(&[big, 4, 0, 7], &[Op::Mul, Op::Mul, Op::Add]),
+            Some(7)
+        );
+        // concatenation right at the top of u64, then past it
+        assert_eq!(
+            solve(u64::MAX, &[1844674407, 3709551615], &ADD_MUL_CONCAT),
+            Some(vec![Op::Concat])
+        );
+        assert_eq!(
+            solve(u64::MAX, &[1844674407, 3709551616], &ADD_MUL_CONCAT),
+            None
+        );
+        assert!(check_line(
+            18446744073709551616u128,
+            &[1844674407, 3709551616],
+            &ADD_MUL_CONCAT
+        ));
     }
 
     #[test]
     fn test_line_setting() {
         let input = "21037: 21037
 292: 0";
-        let (_, lines) = parse(input).unwrap();
-        assert!(check_line(lines[0].0, &lines[0].1));
-        assert!(!check_line(lines[1].0, &lines[1].1));
+        let (_, lines) = parse::<u64>(input).unwrap();
+        assert!(check_line(lines[0].0, &lines[0].1, &ADD_MUL));
+        assert!(!check_line(lines[1].0, &lines[1].1, &ADD_MUL));
     }
 }
@@ -0,0 +1,35 @@
+use day_7::bfs::{ADD_MUL, ADD_MUL_CONCAT};
+use day_7::number::{Equations, Number};
+use day_7::part1::parse_equations;
+use day_7::solutions::{Solvability, report};
+use miette::Context;
+
+fn print_report<N: Number>(lines: &[(N, Vec<N>)]) {
+    for (name, ops) in [("part 1", &ADD_MUL[..]), ("part 2", &ADD_MUL_CONCAT[..])] {
+        println!("{name}");
+        let report = report(lines, ops);
+        for line in &report {
+            println!("{line}");
+        }
+        let count =
+            |pred: fn(&Solvability) -> bool| report.iter().filter(|l| pred(&l.solvability)).count();
+        println!(
+            "{} unique, {} ambiguous, {} unsolvable\n",
+            count(|s| matches!(s, Solvability::Unique(_))),
+            count(|s| matches!(s, Solvability::Ambiguous(..))),
+            count(|s| matches!(s, Solvability::Unsolvable)),
+        );
+    }
+}
+
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let file = include_str!("../../input2.txt");
+    match parse_equations(file).context("parse equations")? {
+        Equations::Narrow(lines) => print_report(&lines),
+        Equations::Wide(lines) => print_report(&lines),
+    }
+    Ok(())
+}
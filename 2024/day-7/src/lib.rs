@@ -1,3 +1,7 @@
 pub mod bfs;
+pub mod number;
 pub mod part1;
 pub mod part2;
+pub mod reverse;
+pub mod solutions;
+pub mod solver;
@@ -0,0 +1,36 @@
+use std::str::FromStr;
+
+use aoc_utils::math::Integer;
+
+/// The unsigned integers an equation can be solved in, on top of the checked
+/// operations of [`Integer`], `None` meaning the result doesn't fit.
+pub trait Number: Integer + FromStr + Into<u128> {
+    /// `self / other` if `other` divides `self`, never for 0.
+    fn exact_div(self, other: Self) -> Option<Self> {
+        (self.checked_rem(other)? == Self::ZERO).then(|| self / other)
+    }
+}
+
+impl Number for u64 {}
+impl Number for u128 {}
+
+/// The equations of an input, in `u64` when every number fits and in `u128`
+/// otherwise.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Equations {
+    Narrow(Vec<(u64, Vec<u64>)>),
+    Wide(Vec<(u128, Vec<u128>)>),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_div() {
+        assert_eq!(12u64.exact_div(4), Some(3));
+        assert_eq!(0u128.exact_div(7), Some(0));
+        assert_eq!(12u64.exact_div(0), None);
+        assert_eq!(12u64.exact_div(5), None);
+    }
+}
@@ -1,6 +1,3 @@
-use crate::bfs::*;
-use core::fmt;
-
 use miette::miette;
 use nom::{
     IResult, Parser,
@@ -11,74 +8,37 @@ use nom::{
     sequence::separated_pair,
 };
 
+use crate::bfs::ADD_MUL;
+use crate::number::{Equations, Number};
+use crate::solver::{Variant, process_with};
+
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, parsed_input) = parse(input).map_err(|e| miette!("Failed to parse input: {}", e))?;
-    let result = chech_lines(parsed_input);
-    Ok(result.to_string())
+    process_with(input, &ADD_MUL, Variant::Backward)
 }
 
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Copy)]
-pub enum Op {
-    Add,
-    Mul,
-}
-
-impl fmt::Display for Op {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Op::Add => write!(f, "➕"),
-            Op::Mul => write!(f, "✖️"),
-        }
+/// The equations in `u64`, or in `u128` as soon as one number doesn't fit.
+pub fn parse_equations(input: &str) -> miette::Result<Equations> {
+    if let Ok((rest, lines)) = parse::<u64>(input)
+        && rest.trim().is_empty()
+    {
+        return Ok(Equations::Narrow(lines));
+    }
+    let (rest, lines) =
+        parse::<u128>(input).map_err(|e| miette!("Failed to parse input: {}", e))?;
+    match rest.trim().lines().next() {
+        None => Ok(Equations::Wide(lines)),
+        Some(line) => Err(miette!("Failed to parse input at: {}", line)),
     }
 }
 
-fn is_valid_line(line: (u64, Vec<u64>)) -> Option<Vec<Op>> {
-    let (target, numbers) = line;
-    dbg!(match (target, &numbers) {
-        (t, n) if n.iter().sum::<u64>() == t => Some(vec![Op::Add; numbers.len()]),
-        (t, n) if n.iter().product::<u64>() == t => Some(vec![Op::Mul; numbers.len()]),
-        (t, n) if n.iter().sum::<u64>() < t => None,
-        (t, n) if n.iter().product::<u64>() > t => None,
-        (t, n) => try_ops(t, n),
-    })
-}
-
-fn try_ops(t: u64, n: &[u64]) -> Option<Vec<Op>> {
-    dbg!(
-        list_res(n)
-            .iter()
-            .find(|(r, _ops)| r == &t)
-            .map(|ops| ops.1.clone())
-    )
-}
-
-fn list_res(input: &[u64]) -> Vec<(u64, Vec<Op>)> {
-    let res = input
-        .windows(2)
-        .fold(Vec::<(u64, Vec<Op>)>::new(), |mut acc, window| {
-            if let [a, b] = window {
-                acc.extend(add_and_mul(*a, *b));
-            }
-            acc
-        });
-    println!("{:?}", res);
-    res
-}
-
-fn add_and_mul(a: u64, b: u64) -> Vec<(u64, Vec<Op>)> {
-    let res = vec![(a + b, vec![Op::Add]), (a * b, vec![Op::Mul])];
-    println!("{:?}", &res);
-    res
-}
-
-pub fn parse(input: &str) -> IResult<&str, Vec<(u64, Vec<u64>)>> {
+pub fn parse<N: Number>(input: &str) -> IResult<&str, Vec<(N, Vec<N>)>> {
     let parsed = separated_list1(
         newline,
         separated_pair(
-            map_res(digit1, |s: &str| s.parse::<u64>()),
+            map_res(digit1, |s: &str| s.parse::<N>()),
             tag(": "),
-            separated_list1(char(' '), map_res(digit1, |s: &str| s.parse::<u64>())),
+            separated_list1(char(' '), map_res(digit1, |s: &str| s.parse::<N>())),
         ),
     )
     .parse(input)?;
@@ -105,10 +65,32 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_equations() -> miette::Result<()> {
+        assert_eq!(
+            parse_equations("3267: 81 40 27\n")?,
+            Equations::Narrow(vec![(3267, vec![81, 40, 27])])
+        );
+        let wide = "18446744073709551616: 1844674407 3709551616\n190: 10 19";
+        assert_eq!(
+            parse_equations(wide)?,
+            Equations::Wide(vec![
+                (18446744073709551616, vec![1844674407, 3709551616]),
+                (190, vec![10, 19])
+            ])
+        );
+        assert_eq!(process(wide)?, "190");
+        assert!(parse_equations("190: 10 19\n3267; 81").is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_parse() -> miette::Result<()> {
         let input = "3267: 81 40 27";
-        assert_eq!(parse(input), Ok(("", vec![(3267, vec![81, 40, 27])])));
+        assert_eq!(
+            parse::<u64>(input),
+            Ok(("", vec![(3267, vec![81, 40, 27])]))
+        );
         Ok(())
     }
 }
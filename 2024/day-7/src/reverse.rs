@@ -0,0 +1,116 @@
+use crate::bfs::Op;
+use crate::number::Number;
+
+/// Same as [`crate::bfs::solve`], searching from the goal back to the first
+/// number.
+///
+/// Every step undoes the last operator with [`Op::unapply`], so a branch dies
+/// as soon as the goal can't be reached from what's left: no subtraction
+/// below zero, no division with a remainder, no un-concatenation of digits
+/// the goal doesn't end with.
+pub fn solve_backward<N: Number>(goal: N, nums: &[N], ops: &[Op]) -> Option<Vec<Op>> {
+    let mut witness = Vec::with_capacity(nums.len().saturating_sub(1));
+    unwind(goal, nums, ops, &mut witness).then_some(witness)
+}
+
+pub fn check_line_backward<N: Number>(goal: N, nums: &[N], ops: &[Op]) -> bool {
+    solve_backward(goal, nums, ops).is_some()
+}
+
+/// Sum of the goals that some choice among `ops` reaches.
+pub fn check_lines_backward<N: Number>(input: &[(N, Vec<N>)], ops: &[Op]) -> u128 {
+    input
+        .iter()
+        .filter(|(goal, nums)| check_line_backward(*goal, nums, ops))
+        .map(|&(goal, _)| goal.into())
+        .sum()
+}
+
+/// Pushes the operators reaching `goal` with `nums` on `witness`, in order.
+fn unwind<N: Number>(goal: N, nums: &[N], ops: &[Op], witness: &mut Vec<Op>) -> bool {
+    let Some((&last, init)) = nums.split_last() else {
+        return false;
+    };
+    if init.is_empty() {
+        return goal == last;
+    }
+    for &op in ops {
+        if op == Op::Mul && last == N::ZERO && goal == N::ZERO {
+            // anything times 0 is the goal, whatever came before
+            witness.extend(std::iter::repeat_n(ops[0], init.len() - 1));
+            witness.push(op);
+            return true;
+        }
+        let Some(prev) = op.unapply(goal, last) else {
+            continue;
+        };
+        if unwind(prev, init, ops, witness) {
+            witness.push(op);
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::bfs::{ADD_MUL, ADD_MUL_CONCAT, check_line, evaluate};
+    use crate::part1::parse;
+
+    const INPUT_TEST: &str = "190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20";
+
+    #[test]
+    fn test_backward() {
+        let (_, lines) = parse::<u64>(INPUT_TEST).unwrap();
+        assert_eq!(check_lines_backward(&lines, &ADD_MUL), 3749);
+        assert_eq!(check_lines_backward(&lines, &ADD_MUL_CONCAT), 11387);
+        assert_eq!(
+            solve_backward(7290u64, &[6, 8, 6, 15], &ADD_MUL_CONCAT),
+            Some(vec![Op::Mul, Op::Concat, Op::Mul])
+        );
+        // the early returns of the forward search that were wrong
+        assert!(check_line_backward(1u64, &[1, 1], &ADD_MUL));
+        assert!(check_line_backward(2u64, &[1, 1], &ADD_MUL));
+        assert!(check_line_backward(0u64, &[5, 7, 0], &ADD_MUL));
+        assert!(!check_line_backward(3u64, &[5], &ADD_MUL));
+    }
+
+    fn equation() -> impl Strategy<Value = (u64, Vec<u64>)> {
+        (
+            prop::collection::vec(0..100u64, 1..6),
+            prop::collection::vec(prop::sample::select(ADD_MUL_CONCAT.to_vec()), 5),
+            any::<bool>(),
+        )
+            .prop_map(|(nums, ops, solvable)| {
+                let goal = evaluate(&nums, &ops).expect("five numbers below 100 fit");
+                // half the goals are off by one, mostly unreachable
+                (if solvable { goal } else { goal + 1 }, nums)
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn backward_agrees_with_bfs((goal, nums) in equation()) {
+            for ops in [&ADD_MUL[..], &ADD_MUL_CONCAT[..]] {
+                let witness = solve_backward(goal, &nums, ops);
+                prop_assert_eq!(witness.is_some(), check_line(goal, &nums, ops));
+                if let Some(witness) = witness {
+                    prop_assert_eq!(witness.len(), nums.len() - 1);
+                    prop_assert!(witness.iter().all(|op| ops.contains(op)));
+                    prop_assert_eq!(evaluate(&nums, &witness), Some(goal));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,221 @@
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::bfs::Op;
+use crate::number::Number;
+
+/// Number of operator assignments, picked from `ops`, that make `nums`
+/// evaluate to `goal`.
+///
+/// Counts every partial result reached at each position. Ops never decrease
+/// a value except a product with 0, so everything past `goal` is merged into
+/// one `None`, the same as an overflow in [`Op::step`]. Zero when `nums` is
+/// empty.
+pub fn count_solutions<N: Number>(goal: N, nums: &[N], ops: &[Op]) -> u64 {
+    let Some((first, rest)) = nums.split_first() else {
+        return 0;
+    };
+    let mut layer = HashMap::from([(Some(*first), 1u64)]);
+    for &n in rest {
+        let mut next = HashMap::with_capacity(layer.len() * ops.len());
+        for (&value, &count) in &layer {
+            for op in ops {
+                let res = op.step(value, n).filter(|&r| r <= goal);
+                *next.entry(res).or_default() += count;
+            }
+        }
+        layer = next;
+    }
+    layer.get(&Some(goal)).copied().unwrap_or(0)
+}
+
+/// Every operator assignment reaching `goal`, in the order of `ops`. None
+/// when `nums` is empty.
+pub fn witnesses<'a, N: Number>(goal: N, nums: &'a [N], ops: &'a [Op]) -> Witnesses<'a, N> {
+    // past `goal` only a 0 still to come can help
+    let mut zero_after = vec![false; nums.len()];
+    for i in (0..nums.len().saturating_sub(1)).rev() {
+        zero_after[i] = zero_after[i + 1] || nums[i + 1] == N::ZERO;
+    }
+    Witnesses {
+        goal,
+        nums,
+        ops,
+        zero_after,
+        stack: nums
+            .first()
+            .map(|&first| (Some(first), Vec::new()))
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Depth first iterator of [`witnesses`].
+pub struct Witnesses<'a, N> {
+    goal: N,
+    nums: &'a [N],
+    ops: &'a [Op],
+    zero_after: Vec<bool>,
+    stack: Vec<(Option<N>, Vec<Op>)>,
+}
+
+impl<N: Number> Iterator for Witnesses<'_, N> {
+    type Item = Vec<Op>;
+
+    fn next(&mut self) -> Option<Vec<Op>> {
+        while let Some((value, path)) = self.stack.pop() {
+            let depth = path.len() + 1;
+            let Some(&n) = self.nums.get(depth) else {
+                if value == Some(self.goal) {
+                    return Some(path);
+                }
+                continue;
+            };
+            for &op in self.ops.iter().rev() {
+                let res = op.step(value, n).filter(|&r| r <= self.goal);
+                if res.is_none() && !self.zero_after[depth] {
+                    continue;
+                }
+                let mut path = path.clone();
+                path.push(op);
+                self.stack.push((res, path));
+            }
+        }
+        None
+    }
+}
+
+/// How many ways an equation is solved.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solvability {
+    Unsolvable,
+    Unique(Vec<Op>),
+    /// The number of assignments and the first of them.
+    Ambiguous(u64, Vec<Op>),
+}
+
+pub fn classify<N: Number>(goal: N, nums: &[N], ops: &[Op]) -> Solvability {
+    let mut all = witnesses(goal, nums, ops);
+    match (all.next(), all.next()) {
+        (None, _) => Solvability::Unsolvable,
+        (Some(only), None) => Solvability::Unique(only),
+        (Some(first), Some(_)) => Solvability::Ambiguous(count_solutions(goal, nums, ops), first),
+    }
+}
+
+/// One line of [`report`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineReport<N> {
+    pub goal: N,
+    pub nums: Vec<N>,
+    pub solvability: Solvability,
+}
+
+impl<N: Number> fmt::Display for LineReport<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nums = self
+            .nums
+            .iter()
+            .map(|n| n.to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let equation = format!("{}: {}", self.goal, nums);
+        let ops = |ops: &[Op]| ops.iter().map(|op| op.to_string()).collect::<String>();
+        match &self.solvability {
+            Solvability::Unsolvable => write!(f, "{equation:<40} ❌"),
+            Solvability::Unique(witness) => write!(f, "{equation:<40} unique     {}", ops(witness)),
+            Solvability::Ambiguous(count, first) => {
+                write!(f, "{equation:<40} {count:>2} ways     {}, ...", ops(first))
+            }
+        }
+    }
+}
+
+/// The solvability of every line.
+pub fn report<N: Number>(lines: &[(N, Vec<N>)], ops: &[Op]) -> Vec<LineReport<N>> {
+    lines
+        .iter()
+        .map(|(goal, nums)| LineReport {
+            goal: *goal,
+            nums: nums.clone(),
+            solvability: classify(*goal, nums, ops),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::bfs::{ADD_MUL, ADD_MUL_CONCAT, evaluate};
+    use crate::reverse::check_line_backward;
+
+    #[test]
+    fn test_count() {
+        assert_eq!(count_solutions(3267u64, &[81, 40, 27], &ADD_MUL), 2);
+        assert_eq!(count_solutions(292u64, &[11, 6, 16, 20], &ADD_MUL), 1);
+        assert_eq!(count_solutions(7290u64, &[6, 8, 6, 15], &ADD_MUL), 0);
+        assert_eq!(count_solutions(7290u64, &[6, 8, 6, 15], &ADD_MUL_CONCAT), 1);
+        // anything before the 0
+        assert_eq!(count_solutions(0u64, &[5, 7, 0], &ADD_MUL), 2);
+        assert_eq!(count_solutions(0u64, &[5, 7, 0], &ADD_MUL_CONCAT), 3);
+        // 1 + 1 * 1 and 1 * 1 + 1
+        assert_eq!(count_solutions(2u64, &[1, 1, 1], &ADD_MUL), 2);
+        assert_eq!(count_solutions(0u64, &[], &ADD_MUL), 0);
+    }
+
+    #[test]
+    fn test_witnesses() {
+        let all: Vec<Vec<Op>> = witnesses(3267u64, &[81, 40, 27], &ADD_MUL).collect();
+        assert_eq!(all, vec![vec![Op::Add, Op::Mul], vec![Op::Mul, Op::Add]]);
+        let big = u64::MAX / 2 + 1;
+        let all: Vec<Vec<Op>> = witnesses(0, &[big, 4, 0], &ADD_MUL).collect();
+        assert_eq!(all, vec![vec![Op::Add, Op::Mul], vec![Op::Mul, Op::Mul]]);
+        assert_eq!(witnesses(83u64, &[17, 5], &ADD_MUL).next(), None);
+        assert_eq!(witnesses(0u64, &[], &ADD_MUL).next(), None);
+    }
+
+    #[test]
+    fn test_report() {
+        let lines = vec![
+            (3267u64, vec![81, 40, 27]),
+            (190, vec![10, 19]),
+            (83, vec![17, 5]),
+            (0, vec![]),
+        ];
+        let report = report(&lines, &ADD_MUL);
+        assert_eq!(
+            report.iter().map(|l| &l.solvability).collect::<Vec<_>>(),
+            [
+                &Solvability::Ambiguous(2, vec![Op::Add, Op::Mul]),
+                &Solvability::Unique(vec![Op::Mul]),
+                &Solvability::Unsolvable,
+                &Solvability::Unsolvable,
+            ]
+        );
+        assert!(report[0].to_string().starts_with("3267: 81 40 27"));
+        assert!(report[0].to_string().contains(" 2 ways"));
+        assert!(report[1].to_string().contains("unique"));
+    }
+
+    proptest! {
+        #[test]
+        fn witnesses_are_counted(
+            nums in prop::collection::vec(0..20u64, 0..6),
+            goal in 0..2000u64,
+        ) {
+            for ops in [&ADD_MUL[..], &ADD_MUL_CONCAT[..]] {
+                let all: Vec<Vec<Op>> = witnesses(goal, &nums, ops).collect();
+                prop_assert_eq!(all.len() as u64, count_solutions(goal, &nums, ops));
+                prop_assert_eq!(!all.is_empty(), check_line_backward(goal, &nums, ops));
+                for witness in &all {
+                    prop_assert_eq!(evaluate(&nums, witness), Some(goal));
+                }
+                let mut distinct = all.clone();
+                distinct.dedup();
+                prop_assert_eq!(distinct.len(), all.len());
+            }
+        }
+    }
+}
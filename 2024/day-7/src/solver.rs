@@ -0,0 +1,78 @@
+use crate::bfs::{Op, check_lines};
+use crate::number::{Equations, Number};
+use crate::part1::parse_equations;
+use crate::reverse::check_lines_backward;
+
+/// Which search finds the operators of an equation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Variant {
+    /// Undoes operators from the goal back to the first number.
+    Backward,
+    /// Tries every operator forward from the first number.
+    Bfs,
+}
+
+impl Variant {
+    pub const ALL: [Variant; 2] = [Variant::Backward, Variant::Bfs];
+}
+
+/// Sum of the goals of `input` that some choice among `ops` reaches.
+#[tracing::instrument(skip(input))]
+pub fn process_with(input: &str, ops: &[Op], variant: Variant) -> miette::Result<String> {
+    let result = match parse_equations(input)? {
+        Equations::Narrow(lines) => sum_solvable(lines, ops, variant),
+        Equations::Wide(lines) => sum_solvable(lines, ops, variant),
+    };
+    Ok(result.to_string())
+}
+
+fn sum_solvable<N: Number>(lines: Vec<(N, Vec<N>)>, ops: &[Op], variant: Variant) -> u128 {
+    match variant {
+        Variant::Backward => check_lines_backward(&lines, ops),
+        Variant::Bfs => check_lines(lines, ops),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bfs::{ADD_MUL, ADD_MUL_CONCAT};
+
+    const INPUT_TEST: &str = "190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20";
+
+    #[test]
+    fn test_variants_agree() -> miette::Result<()> {
+        // concatenation blows up the forward search on the full input, which
+        // takes a minute in a debug build, so only part 1 there
+        let input = include_str!("../input1.txt");
+        let expected = process_with(input, &ADD_MUL, Variant::Backward)?;
+        for variant in Variant::ALL {
+            assert_eq!("3749", process_with(INPUT_TEST, &ADD_MUL, variant)?);
+            assert_eq!("11387", process_with(INPUT_TEST, &ADD_MUL_CONCAT, variant)?);
+            assert_eq!(expected, process_with(input, &ADD_MUL, variant)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_wide() -> miette::Result<()> {
+        // 2^64 is out of reach of u64, and of part 1
+        let input = "18446744073709551616: 1844674407 3709551616\n190: 10 19";
+        for variant in Variant::ALL {
+            assert_eq!("190", process_with(input, &ADD_MUL, variant)?);
+            assert_eq!(
+                "18446744073709551806",
+                process_with(input, &ADD_MUL_CONCAT, variant)?
+            );
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,252 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::Neg;
+
+use aoc_utils::geometry::line_points;
+use aoc_utils::parsing::Pos as GridPos;
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Hash, Clone, Copy)]
+pub struct Pos {
+    pub y: i32,
+    pub x: i32,
+}
+
+impl From<(usize, usize)> for Pos {
+    fn from(value: (usize, usize)) -> Self {
+        Self {
+            y: value.0 as i32,
+            x: value.1 as i32,
+        }
+    }
+}
+
+impl From<Pos> for GridPos<i64> {
+    fn from(value: Pos) -> Self {
+        GridPos {
+            line: value.y as i64,
+            column: value.x as i64,
+        }
+    }
+}
+
+impl From<GridPos<i64>> for Pos {
+    fn from(value: GridPos<i64>) -> Self {
+        Pos {
+            y: value.line as i32,
+            x: value.column as i32,
+        }
+    }
+}
+
+impl Pos {
+    /// Returns true if the position is inside a map of `size`.
+    pub fn is_inside(&self, size: Size) -> bool {
+        (0..size.width).contains(&self.x) && (0..size.height).contains(&self.y)
+    }
+
+    /// Vector difference between two positions.
+    pub fn delta(self, other: Pos) -> Pos {
+        Pos {
+            x: self.x - other.x,
+            y: self.y - other.y,
+        }
+    }
+
+    /// Shift this position by a vector.
+    pub fn shifted(self, v: Pos) -> Pos {
+        Pos {
+            x: self.x + v.x,
+            y: self.y + v.y,
+        }
+    }
+}
+
+impl Neg for Pos {
+    type Output = Pos;
+
+    /// The opposite vector.
+    fn neg(self) -> Pos {
+        Pos {
+            x: -self.x,
+            y: -self.y,
+        }
+    }
+}
+
+/// Dimensions of a map, not necessarily square.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Size {
+    pub height: i32,
+    pub width: i32,
+}
+
+impl Size {
+    /// The size of the map drawn in `input`, as wide as its longest line.
+    pub fn of(input: &str) -> Size {
+        Size {
+            height: input.lines().count() as i32,
+            width: input.lines().map(|l| l.chars().count()).max().unwrap_or(0) as i32,
+        }
+    }
+}
+
+/// Antenna positions by frequency.
+pub type FrequencyMap = HashMap<char, Vec<Pos>>;
+
+/// Every antenna of the map, `.` being empty ground.
+pub fn parse(input: &str) -> FrequencyMap {
+    let mut freq_maps: FrequencyMap = HashMap::new();
+    for (ln, line) in input.lines().enumerate() {
+        for (cn, char) in line.chars().enumerate() {
+            if char != '.' {
+                freq_maps.entry(char).or_default().push(Pos::from((ln, cn)));
+            }
+        }
+    }
+    freq_maps
+}
+
+/// Which antinodes a pair of antennas creates.
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum Harmonics {
+    /// Part 1: one on each side, twice as far from one antenna as from the
+    /// other.
+    Off,
+    /// Part 2: every grid position in line with the two antennas, both
+    /// included.
+    Resonant,
+}
+
+/// The antinodes of every frequency, kept apart from the antennas so any
+/// character can be a frequency.
+#[derive(PartialEq, Eq, Debug, Default, Clone)]
+pub struct AntinodeMap {
+    by_frequency: HashMap<char, HashSet<Pos>>,
+}
+
+impl AntinodeMap {
+    pub fn get(&self, freq: char) -> Option<&HashSet<Pos>> {
+        self.by_frequency.get(&freq)
+    }
+
+    /// Every distinct antinode position, whatever its frequency.
+    pub fn positions(&self) -> HashSet<Pos> {
+        self.by_frequency.values().flatten().copied().collect()
+    }
+
+    /// Number of distinct antinode positions.
+    pub fn len(&self) -> usize {
+        self.positions().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_frequency.values().all(HashSet::is_empty)
+    }
+
+    /// A map of `size` with a `#` on every antinode, whatever its frequency.
+    pub fn render(&self, size: Size) -> String {
+        let positions = self.positions();
+        (0..size.height)
+            .map(|y| {
+                (0..size.width)
+                    .map(|x| {
+                        if positions.contains(&Pos { y, x }) {
+                            '#'
+                        } else {
+                            '.'
+                        }
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// The antinodes of the antennas `a` and `b` inside a map of `size`.
+pub fn set_antinode(a: Pos, b: Pos, size: Size, harmonics: Harmonics) -> Vec<Pos> {
+    // Vector from b to a
+    let v_ab = a.delta(b);
+    match harmonics {
+        Harmonics::Off => [a.shifted(v_ab), b.shifted(-v_ab)]
+            .into_iter()
+            .filter(|p| p.is_inside(size))
+            .collect(),
+        // every lattice point of the line, antennas 2 apart in one direction
+        // and 4 in the other have one in between
+        Harmonics::Resonant => {
+            line_points(a.into(), b.into(), size.height as i64, size.width as i64)
+                .into_iter()
+                .map(Pos::from)
+                .collect()
+        }
+    }
+}
+
+/// The antinodes of every pair of antennas sharing a frequency.
+pub fn build_antinode_map(
+    freq_maps: &FrequencyMap,
+    size: Size,
+    harmonics: Harmonics,
+) -> AntinodeMap {
+    let mut map = AntinodeMap::default();
+    for (&freq, antennas) in freq_maps {
+        let antinodes = map.by_frequency.entry(freq).or_default();
+        for (i, &a) in antennas.iter().enumerate() {
+            for &b in &antennas[i + 1..] {
+                antinodes.extend(set_antinode(a, b, size, harmonics));
+            }
+        }
+    }
+    map
+}
+
+/// Number of distinct antinode positions on the map drawn in `input`.
+pub fn count_antinodes(input: &str, harmonics: Harmonics) -> usize {
+    build_antinode_map(&parse(input), Size::of(input), harmonics).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_harmonics() {
+        let size = Size {
+            height: 3,
+            width: 10,
+        };
+        let a = Pos { y: 0, x: 0 };
+        let b = Pos { y: 2, x: 4 };
+        // a gcd of 2, (1, 2) is halfway between
+        let mut resonant = set_antinode(a, b, size, Harmonics::Resonant);
+        resonant.sort();
+        assert_eq!(resonant, vec![a, Pos { y: 1, x: 2 }, b]);
+        assert_eq!(set_antinode(a, b, size, Harmonics::Off), vec![]);
+        assert_eq!(
+            set_antinode(Pos { y: 1, x: 2 }, b, size, Harmonics::Off),
+            vec![a]
+        );
+    }
+
+    #[test]
+    fn test_rectangular() {
+        // wider than tall, and a '#' antenna
+        let input = "#.........\n..........\n..#.......";
+        assert_eq!(
+            Size::of(input),
+            Size {
+                height: 3,
+                width: 10
+            }
+        );
+        assert_eq!(count_antinodes(input, Harmonics::Off), 0);
+        let map = build_antinode_map(&parse(input), Size::of(input), Harmonics::Resonant);
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get('#').map(HashSet::len), Some(3));
+        assert_eq!(map.get('A'), None);
+        assert_eq!(
+            map.render(Size::of(input)),
+            "#.........\n.#........\n..#......."
+        );
+    }
+}